@@ -0,0 +1,164 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::context::Interrupted;
+use crate::hash::{self, stable_hash};
+
+/// A file that an exhaustive search periodically saves its frontier to
+///
+/// The saved state is tagged with a hash of the puzzle input so a checkpoint
+/// can't be resumed against a different input. The hash's algorithm is saved
+/// with it, so a checkpoint keeps working across toolchain upgrades.
+pub struct Checkpoint {
+    path: PathBuf,
+    interval: Duration,
+    last_saved: Mutex<Instant>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Saved<T> {
+    hash: String,
+    input: u64,
+    state: T,
+}
+
+fn input_hash(input: &str) -> u64 {
+    stable_hash(input.as_bytes())
+}
+
+/// Why a checkpointed search stopped without an answer
+#[derive(Debug)]
+pub enum SearchError {
+    Interrupted(Interrupted),
+    /// The checkpoint couldn't be loaded, saved or removed
+    Checkpoint(io::Error),
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::Interrupted(interrupted) => write!(f, "{}", interrupted),
+            SearchError::Checkpoint(err) => write!(f, "checkpoint failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+impl From<Interrupted> for SearchError {
+    fn from(interrupted: Interrupted) -> Self {
+        SearchError::Interrupted(interrupted)
+    }
+}
+
+impl From<io::Error> for SearchError {
+    fn from(err: io::Error) -> Self {
+        SearchError::Checkpoint(err)
+    }
+}
+
+impl Checkpoint {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration) -> Self {
+        Self {
+            path: path.into(),
+            interval,
+            last_saved: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Loads the saved state, or `None` if nothing has been saved yet
+    pub fn load<T: DeserializeOwned>(&self, input: &str) -> io::Result<Option<T>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let saved: Saved<T> = serde_json::from_str(&text)?;
+        if saved.hash != hash::ALGORITHM {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "checkpoint {} was saved with an unknown input hash, delete it to start again",
+                    self.path.display()
+                ),
+            ));
+        }
+        if saved.input != input_hash(input) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "checkpoint {} was saved for a different input",
+                    self.path.display()
+                ),
+            ));
+        }
+        Ok(Some(saved.state))
+    }
+
+    /// Writes the state next to the checkpoint and then moves it into place so
+    /// an interrupted save never leaves a truncated file behind
+    pub fn save<T: Serialize>(&self, input: &str, state: &T) -> io::Result<()> {
+        let saved = Saved {
+            hash: hash::ALGORITHM.to_string(),
+            input: input_hash(input),
+            state,
+        };
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string(&saved)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        *self.last_saved.lock().unwrap() = Instant::now();
+        Ok(())
+    }
+
+    /// Whether the save interval has passed since the last save
+    pub fn due(&self) -> bool {
+        self.last_saved.lock().unwrap().elapsed() >= self.interval
+    }
+
+    /// Removes the checkpoint once the search it belongs to has finished
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+        let checkpoint = Checkpoint::new(&path, Duration::from_secs(60));
+        assert_eq!(checkpoint.load::<Vec<u32>>("input").unwrap(), None);
+        checkpoint.save("input", &vec![1, 2, 3]).unwrap();
+        assert_eq!(
+            checkpoint.load::<Vec<u32>>("input").unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert!(checkpoint.load::<Vec<u32>>("other input").is_err());
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["hash"], "fnv1a-64");
+        assert_eq!(saved["input"], stable_hash(b"input"));
+        let other = format!(
+            r#"{{"hash":"sip","input":{},"state":[1,2,3]}}"#,
+            stable_hash(b"input")
+        );
+        fs::write(&path, other).unwrap();
+        assert!(checkpoint.load::<Vec<u32>>("input").is_err());
+        assert!(!checkpoint.due());
+        checkpoint.clear().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::checkpoint::Checkpoint;
+
+/// Shared flag that lets another thread ask a running solver to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clears a cancellation so the token can be used for the next run
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Receives progress updates from long running solver loops
+///
+/// `total` is the number of units of work if the solver knows it up front.
+pub trait Progress: Send + Sync {
+    fn start(&self, _total: Option<u64>) {}
+    fn advance(&self, _delta: u64) {}
+    fn finish(&self) {}
+}
+
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
+
+/// Draws progress as an `indicatif` bar on stderr
+pub struct BarProgress(ProgressBar);
+
+impl BarProgress {
+    pub fn new() -> Self {
+        Self(ProgressBar::new(0))
+    }
+}
+
+impl Default for BarProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Progress for BarProgress {
+    fn start(&self, total: Option<u64>) {
+        match total {
+            Some(total) => self.0.set_length(total),
+            None => self.0.set_style(ProgressStyle::default_spinner()),
+        }
+    }
+
+    fn advance(&self, delta: u64) {
+        self.0.inc(delta);
+    }
+
+    fn finish(&self) {
+        self.0.finish();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    Cancelled,
+    DeadlineExceeded,
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interrupted::Cancelled => write!(f, "cancelled"),
+            Interrupted::DeadlineExceeded => write!(f, "deadline exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Everything a long running solver needs from whoever is running it
+///
+/// Solvers call `check` once per iteration of their main loop and bail out
+/// with the returned error, and report how far they've got through `progress`.
+#[derive(Clone)]
+pub struct SolverContext {
+    cancel: CancellationToken,
+    deadline: Option<Instant>,
+    progress: Arc<dyn Progress>,
+    checkpoint: Option<Arc<Checkpoint>>,
+}
+
+impl Default for SolverContext {
+    fn default() -> Self {
+        Self {
+            cancel: CancellationToken::new(),
+            deadline: None,
+            progress: Arc::new(NoProgress),
+            checkpoint: None,
+        }
+    }
+}
+
+impl SolverContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    pub fn with_checkpoint(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = Some(Arc::new(checkpoint));
+        self
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    pub fn progress(&self) -> &dyn Progress {
+        self.progress.as_ref()
+    }
+
+    /// Where solvers that support it should save and resume their search
+    pub fn checkpoint(&self) -> Option<&Checkpoint> {
+        self.checkpoint.as_deref()
+    }
+
+    pub fn check(&self) -> Result<(), Interrupted> {
+        if self.cancel.is_cancelled() {
+            Err(Interrupted::Cancelled)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Err(Interrupted::DeadlineExceeded)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    #[test]
+    fn cancellation() {
+        let token = CancellationToken::new();
+        let ctx = SolverContext::new().with_cancellation(token.clone());
+        assert_eq!(ctx.check(), Ok(()));
+        token.cancel();
+        assert_eq!(ctx.check(), Err(Interrupted::Cancelled));
+    }
+
+    #[test]
+    fn deadline() {
+        let ctx = SolverContext::new().with_timeout(Duration::ZERO);
+        assert_eq!(ctx.check(), Err(Interrupted::DeadlineExceeded));
+        let ctx = SolverContext::new().with_timeout(Duration::from_secs(60));
+        assert_eq!(ctx.check(), Ok(()));
+    }
+
+    #[test]
+    fn progress() {
+        struct Counter(AtomicU64);
+        impl Progress for Counter {
+            fn advance(&self, delta: u64) {
+                self.0.fetch_add(delta, Ordering::Relaxed);
+            }
+        }
+        let counter = Arc::new(Counter(AtomicU64::new(0)));
+        let ctx = SolverContext::new().with_progress(counter.clone());
+        ctx.progress().advance(2);
+        ctx.clone().progress().advance(3);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 5);
+    }
+}
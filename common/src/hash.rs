@@ -0,0 +1,68 @@
+/// The name saved alongside hashes so files written with another algorithm
+/// are recognised rather than silently mismatching
+pub const ALGORITHM: &str = "fnv1a-64";
+
+const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const PRIME: u64 = 0x100000001b3;
+
+/// A 64 bit FNV-1a hash of bytes
+///
+/// Unlike `DefaultHasher` the result never changes between runs, builds or
+/// Rust releases, so it's safe to save to disk.
+#[derive(Debug, Clone)]
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self(OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(PRIME);
+        }
+    }
+
+    /// Writes a length prefixed field, so `("ab", "c")` and `("a", "bc")`
+    /// hash differently
+    pub fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        assert_eq!(stable_hash(b""), 0xcbf29ce484222325);
+        assert_eq!(stable_hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(stable_hash(b"foobar"), 0x85944171f73967e8);
+        let fields = |a: &str, b: &str| {
+            let mut hasher = StableHasher::new();
+            hasher.write_field(a.as_bytes());
+            hasher.write_field(b.as_bytes());
+            hasher.finish()
+        };
+        assert_ne!(fields("ab", "c"), fields("a", "bc"));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod checkpoint;
+pub mod context;
+pub mod hash;
+pub mod search;
+pub mod simulation;
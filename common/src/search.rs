@@ -0,0 +1,280 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+/// A state space to be searched from a start state for a goal state
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+
+    fn start(&self) -> Self::State;
+
+    /// The states reachable in one move from `state` and what each move costs
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, u64)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// A lower bound on the cost from `state` to the nearest goal, only used by
+    /// `astar`
+    fn heuristic(&self, _state: &Self::State) -> u64 {
+        0
+    }
+}
+
+/// A path from the start to a goal, and how many states were expanded to find it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<S> {
+    pub path: Vec<S>,
+    pub cost: u64,
+    pub expanded: usize,
+}
+
+/// Walks back through the parent links from the state at `idx`
+fn reconstruct<S: Clone>(states: &[(S, Option<usize>)], mut idx: usize) -> Vec<S> {
+    let mut path = vec![states[idx].0.clone()];
+    while let Some(parent) = states[idx].1 {
+        path.push(states[parent].0.clone());
+        idx = parent;
+    }
+    path.reverse();
+    path
+}
+
+/// Finds the path with the fewest moves, summing the move costs along it
+pub fn bfs<P: SearchProblem>(problem: &P) -> Option<Solution<P::State>> {
+    let start = problem.start();
+    let mut states = vec![(start.clone(), None)];
+    let mut costs = vec![0];
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([0]);
+    let mut expanded = 0;
+    while let Some(idx) = queue.pop_front() {
+        expanded += 1;
+        if problem.is_goal(&states[idx].0) {
+            return Some(Solution {
+                path: reconstruct(&states, idx),
+                cost: costs[idx],
+                expanded,
+            });
+        }
+        for (next, cost) in problem.successors(&states[idx].0) {
+            if seen.insert(next.clone()) {
+                states.push((next, Some(idx)));
+                costs.push(costs[idx] + cost);
+                queue.push_back(states.len() - 1);
+            }
+        }
+    }
+    None
+}
+
+/// Every state within `max_depth` moves of the start
+#[derive(Debug)]
+pub struct Reachable<S> {
+    /// The fewest moves needed to reach each state
+    pub depths: HashMap<S, usize>,
+    pub expanded: usize,
+}
+
+/// Breadth first search that stops after `max_depth` moves instead of at a goal
+pub fn reachable<P: SearchProblem>(problem: &P, max_depth: usize) -> Reachable<P::State> {
+    let start = problem.start();
+    let mut depths = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut expanded = 0;
+    while let Some((state, depth)) = queue.pop_front() {
+        if depth == max_depth {
+            continue;
+        }
+        expanded += 1;
+        for (next, _) in problem.successors(&state) {
+            if !depths.contains_key(&next) {
+                depths.insert(next.clone(), depth + 1);
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+    Reachable { depths, expanded }
+}
+
+fn best_first<P: SearchProblem>(
+    problem: &P,
+    heuristic: impl Fn(&P::State) -> u64,
+) -> Option<Solution<P::State>> {
+    let start = problem.start();
+    let mut states = vec![(start.clone(), None)];
+    let mut best = HashMap::from([(start.clone(), (0, 0))]);
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut expanded = 0;
+    while let Some(Reverse((_, cost, idx))) = heap.pop() {
+        // Skip states that were already reached more cheaply
+        if best[&states[idx].0].0 < cost {
+            continue;
+        }
+        expanded += 1;
+        if problem.is_goal(&states[idx].0) {
+            return Some(Solution {
+                path: reconstruct(&states, idx),
+                cost,
+                expanded,
+            });
+        }
+        for (next, move_cost) in problem.successors(&states[idx].0) {
+            let next_cost = cost + move_cost;
+            if best.get(&next).is_some_and(|&(best_cost, _)| best_cost <= next_cost) {
+                continue;
+            }
+            let priority = next_cost + heuristic(&next);
+            states.push((next.clone(), Some(idx)));
+            best.insert(next, (next_cost, states.len() - 1));
+            heap.push(Reverse((priority, next_cost, states.len() - 1)));
+        }
+    }
+    None
+}
+
+/// Finds the cheapest path to a goal
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> Option<Solution<P::State>> {
+    best_first(problem, |_| 0)
+}
+
+/// Finds the cheapest path to a goal, guided by the problem's heuristic
+pub fn astar<P: SearchProblem>(problem: &P) -> Option<Solution<P::State>> {
+    best_first(problem, |state| problem.heuristic(state))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "S: Serialize",
+    deserialize = "S: Deserialize<'de> + Eq + Hash"
+))]
+struct PartialPath<S: Eq + Hash> {
+    path: Vec<S>,
+    visited: HashSet<S>,
+    cost: u64,
+}
+
+/// Depth first search for the most expensive path to a goal that never
+/// revisits a state
+///
+/// The search is driven one expansion at a time so it can be interrupted and,
+/// for serializable states, saved and resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "S: Serialize",
+    deserialize = "S: Deserialize<'de> + Eq + Hash"
+))]
+pub struct LongestPath<S: Eq + Hash> {
+    pending: Vec<PartialPath<S>>,
+    longest: Option<PartialPath<S>>,
+    expanded: usize,
+}
+
+impl<S: Clone + Eq + Hash> LongestPath<S> {
+    pub fn new<P: SearchProblem<State = S>>(problem: &P) -> Self {
+        let start = problem.start();
+        Self {
+            pending: vec![PartialPath {
+                path: vec![start.clone()],
+                visited: HashSet::from([start]),
+                cost: 0,
+            }],
+            longest: None,
+            expanded: 0,
+        }
+    }
+
+    /// Expands the next pending path, or returns `false` if there are none left
+    pub fn expand<P: SearchProblem<State = S>>(&mut self, problem: &P) -> bool {
+        let Some(partial) = self.pending.pop() else {
+            return false;
+        };
+        self.expanded += 1;
+        let state = partial.path.last().unwrap();
+        if problem.is_goal(state) {
+            if self.longest.as_ref().is_none_or(|longest| longest.cost < partial.cost) {
+                self.longest = Some(partial);
+            }
+            return true;
+        }
+        for (next, cost) in problem.successors(state) {
+            if !partial.visited.contains(&next) {
+                let mut next_partial = partial.clone();
+                next_partial.path.push(next.clone());
+                next_partial.visited.insert(next);
+                next_partial.cost += cost;
+                self.pending.push(next_partial);
+            }
+        }
+        true
+    }
+
+    pub fn into_solution(self) -> Option<Solution<S>> {
+        self.longest.map(|longest| Solution {
+            path: longest.path,
+            cost: longest.cost,
+            expanded: self.expanded,
+        })
+    }
+}
+
+pub fn longest_path<P: SearchProblem>(problem: &P) -> Option<Solution<P::State>> {
+    let mut search = LongestPath::new(problem);
+    while search.expand(problem) {}
+    search.into_solution()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted graph with a cheap long way round and an expensive
+    /// shortcut from 0 to 3
+    struct Graph;
+
+    impl SearchProblem for Graph {
+        type State = u32;
+
+        fn start(&self) -> u32 {
+            0
+        }
+
+        fn successors(&self, state: &u32) -> Vec<(u32, u64)> {
+            match state {
+                0 => vec![(1, 1), (3, 10)],
+                1 => vec![(2, 1), (0, 1)],
+                2 => vec![(3, 1)],
+                _ => vec![],
+            }
+        }
+
+        fn is_goal(&self, state: &u32) -> bool {
+            *state == 3
+        }
+
+        fn heuristic(&self, state: &u32) -> u64 {
+            3 - *state as u64
+        }
+    }
+
+    #[test]
+    fn backends() {
+        let solution = bfs(&Graph).unwrap();
+        assert_eq!((solution.path, solution.cost), (vec![0, 3], 10));
+        for solution in [dijkstra(&Graph).unwrap(), astar(&Graph).unwrap()] {
+            assert_eq!((solution.path, solution.cost), (vec![0, 1, 2, 3], 3));
+        }
+        assert!(astar(&Graph).unwrap().expanded <= dijkstra(&Graph).unwrap().expanded);
+        let solution = longest_path(&Graph).unwrap();
+        assert_eq!((solution.path, solution.cost), (vec![0, 3], 10));
+    }
+
+    #[test]
+    fn reachable_within_depth() {
+        let reachable = reachable(&Graph, 2);
+        assert_eq!(reachable.depths.len(), 4);
+        assert_eq!(reachable.depths[&2], 2);
+        assert_eq!(reachable.depths[&3], 1);
+    }
+}
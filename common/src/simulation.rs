@@ -0,0 +1,192 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+/// A puzzle system that evolves one discrete step at a time
+pub trait Simulation {
+    /// A copy of the system's state that outlives the step it was taken at
+    type Snapshot;
+
+    /// Advances the system by one step, or returns `false` if it has already
+    /// run to completion
+    fn step(&mut self) -> bool;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Steps until `done` holds or the system runs to completion and returns
+    /// the number of steps taken
+    fn run_until(&mut self, done: impl FnMut(&Self) -> bool) -> usize
+    where
+        Self: Sized,
+    {
+        self.observe(done, |_| {})
+    }
+
+    /// Like `run_until`, but hands the system to `observer` after every step
+    fn observe(
+        &mut self,
+        mut done: impl FnMut(&Self) -> bool,
+        mut observer: impl FnMut(&Self),
+    ) -> usize
+    where
+        Self: Sized,
+    {
+        let mut steps = 0;
+        while !done(self) && self.step() {
+            steps += 1;
+            observer(self);
+        }
+        steps
+    }
+}
+
+/// The states a simulation went through before it started repeating itself
+#[derive(Debug)]
+pub struct Cycle<T> {
+    /// Step at which the repeating section starts
+    pub start: usize,
+    pub len: usize,
+    states: Vec<T>,
+}
+
+impl<T> Cycle<T> {
+    /// The state the simulation would be in after `step` steps
+    pub fn state_at(&self, step: usize) -> &T {
+        if step < self.states.len() {
+            &self.states[step]
+        } else {
+            &self.states[self.start + (step - self.start) % self.len]
+        }
+    }
+}
+
+/// Steps the simulation until a snapshot repeats, or returns `None` if it
+/// runs to completion first
+///
+/// Each snapshot is only kept once, in the cycle's states. Lookups go through
+/// the snapshots' hashes, with snapshots sharing a hash chained together by
+/// index.
+pub fn find_cycle<S>(sim: &mut S) -> Option<Cycle<S::Snapshot>>
+where
+    S: Simulation,
+    S::Snapshot: Hash + Eq,
+{
+    let hasher = RandomState::new();
+    // The latest step seen with each hash
+    let mut latest: HashMap<u64, usize> = HashMap::new();
+    // The step before each one with the same hash
+    let mut previous: Vec<Option<usize>> = Vec::new();
+    let mut states = Vec::new();
+    loop {
+        let snapshot = sim.snapshot();
+        let hash = hasher.hash_one(&snapshot);
+        let mut candidate = latest.get(&hash).copied();
+        while let Some(start) = candidate {
+            if states[start] == snapshot {
+                return Some(Cycle {
+                    start,
+                    len: states.len() - start,
+                    states,
+                });
+            }
+            candidate = previous[start];
+        }
+        previous.push(latest.insert(hash, states.len()));
+        states.push(snapshot);
+        if !sim.step() {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts up from `value` and wraps back to 2 after 6
+    struct Counter {
+        value: u32,
+        limit: Option<u32>,
+    }
+
+    impl Simulation for Counter {
+        type Snapshot = u32;
+
+        fn step(&mut self) -> bool {
+            if self.limit == Some(self.value) {
+                return false;
+            }
+            self.value = if self.value == 6 { 2 } else { self.value + 1 };
+            true
+        }
+
+        fn snapshot(&self) -> u32 {
+            self.value
+        }
+    }
+
+    #[test]
+    fn run_until() {
+        let mut counter = Counter {
+            value: 0,
+            limit: Some(4),
+        };
+        assert_eq!(counter.run_until(|counter| counter.value == 3), 3);
+        let mut seen = Vec::new();
+        assert_eq!(counter.observe(|_| false, |counter| seen.push(counter.value)), 1);
+        assert_eq!(seen, vec![4]);
+    }
+
+    #[test]
+    fn cycle() {
+        let mut counter = Counter {
+            value: 0,
+            limit: None,
+        };
+        let cycle = find_cycle(&mut counter).unwrap();
+        assert_eq!((cycle.start, cycle.len), (2, 5));
+        assert_eq!(*cycle.state_at(1), 1);
+        assert_eq!(*cycle.state_at(7), 2);
+        assert_eq!(*cycle.state_at(1000), 2 + (1000 - 2) % 5);
+        let mut counter = Counter {
+            value: 0,
+            limit: Some(4),
+        };
+        assert!(find_cycle(&mut counter).is_none());
+    }
+
+    /// A counter value that only hashes its parity, so most snapshots collide
+    #[derive(Debug, PartialEq, Eq)]
+    struct Parity(u32);
+
+    impl Hash for Parity {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            (self.0 % 2).hash(state);
+        }
+    }
+
+    struct Colliding(Counter);
+
+    impl Simulation for Colliding {
+        type Snapshot = Parity;
+
+        fn step(&mut self) -> bool {
+            self.0.step()
+        }
+
+        fn snapshot(&self) -> Parity {
+            Parity(self.0.value)
+        }
+    }
+
+    #[test]
+    fn cycle_with_colliding_hashes() {
+        let mut counter = Colliding(Counter {
+            value: 0,
+            limit: None,
+        });
+        let cycle = find_cycle(&mut counter).unwrap();
+        assert_eq!((cycle.start, cycle.len), (2, 5));
+        assert_eq!(*cycle.state_at(8), Parity(3));
+    }
+}
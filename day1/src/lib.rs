@@ -1,68 +1,520 @@
-use nom::character::complete::*;
-use nom::{branch::alt, bytes::complete::*, combinator::*, multi::*, sequence::*, IResult};
-use std::str;
-
-fn parse1(text: &str) -> IResult<&str, i32> {
-    let (_, digits) = many1(preceded(alpha0, anychar))(text)?;
-    let num_str = String::from_iter([digits[0], digits[digits.len() - 1]].iter());
-    Ok(("", num_str.parse::<i32>().unwrap()))
-}
-
-fn map_spelling(num_str: &str) -> char {
-    match num_str {
-        "zero" => '0',
-        "one" => '1',
-        "two" => '2',
-        "three" => '3',
-        "four" => '4',
-        "five" => '5',
-        "six" => '6',
-        "seven" => '7',
-        "eight" => '8',
-        "nine" => '9',
-        _ => panic!(),
-    }
-}
-
-fn my_digit(text: &str) -> IResult<&str, char> {
-    terminated(
-        peek(alt((
-            one_of("0123456789"),
-            map(tag("zero"), map_spelling),
-            map(tag("one"), map_spelling),
-            map(tag("two"), map_spelling),
-            map(tag("three"), map_spelling),
-            map(tag("four"), map_spelling),
-            map(tag("five"), map_spelling),
-            map(tag("six"), map_spelling),
-            map(tag("seven"), map_spelling),
-            map(tag("eight"), map_spelling),
-            map(tag("nine"), map_spelling),
-        ))),
-        anychar,
-    )(text)
-}
-
-fn parse2(text: &str) -> IResult<&str, i32> {
-    let (_, digits) = many0(preceded(many0(pair(not(my_digit), anychar)), my_digit))(text)?;
-    let num_str = String::from_iter([digits[0], digits[digits.len() - 1]].iter());
-    Ok(("", num_str.parse::<i32>().unwrap()))
-}
-
-pub fn part1(text: String) -> i32 {
-    let mut sum = 0;
-    for line in text.lines() {
-        let (_, num) = parse1(line.trim()).unwrap();
-        sum += num;
-    }
-    sum
-}
-
-pub fn part2(text: String) -> i32 {
-    let mut sum = 0;
-    for line in text.lines() {
-        let (_, num) = parse2(line.trim()).unwrap();
-        sum += num;
-    }
-    sum
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use nom::{bytes::complete::take_till1, character::complete::space1, sequence::separated_pair};
+use parsing::{number, parse_all, solve_lines, Located};
+
+const ENGLISH: &[(&str, u32)] = &[
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const ENGLISH_EXTENDED: &[(&str, u32)] = &[
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+    ("sixteen", 16),
+    ("seventeen", 17),
+    ("eighteen", 18),
+    ("nineteen", 19),
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+
+const SPANISH: &[(&str, u32)] = &[
+    ("cero", 0),
+    ("uno", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+    ("diez", 10),
+    ("once", 11),
+    ("doce", 12),
+    ("trece", 13),
+    ("catorce", 14),
+    ("quince", 15),
+    ("dieciséis", 16),
+    ("diecisiete", 17),
+    ("dieciocho", 18),
+    ("diecinueve", 19),
+    ("veinte", 20),
+    ("treinta", 30),
+    ("cuarenta", 40),
+    ("cincuenta", 50),
+    ("sesenta", 60),
+    ("setenta", 70),
+    ("ochenta", 80),
+    ("noventa", 90),
+];
+
+const GERMAN: &[(&str, u32)] = &[
+    ("null", 0),
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+    ("zehn", 10),
+    ("elf", 11),
+    ("zwölf", 12),
+    ("dreizehn", 13),
+    ("vierzehn", 14),
+    ("fünfzehn", 15),
+    ("sechzehn", 16),
+    ("siebzehn", 17),
+    ("achtzehn", 18),
+    ("neunzehn", 19),
+    ("zwanzig", 20),
+    ("dreißig", 30),
+    ("vierzig", 40),
+    ("fünfzig", 50),
+    ("sechzig", 60),
+    ("siebzig", 70),
+    ("achtzig", 80),
+    ("neunzig", 90),
+];
+
+/// The names of the built in vocabularies
+pub const BUILTIN: &[&str] = &["english", "english-extended", "spanish", "german"];
+
+/// Spelled out numbers and the values they stand for
+///
+/// Numeric digits are always recognised on top of the words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    /// Sorted longest first, so the same words always make the same vocabulary
+    words: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    pub fn new(words: impl IntoIterator<Item = (String, u32)>) -> Self {
+        let mut words: Vec<_> = words.into_iter().collect();
+        words.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+        Self { words }
+    }
+
+    /// Only the numeric digits, as in part 1
+    pub fn digits() -> Self {
+        Self::new([])
+    }
+
+    /// `zero` to `nine`, as in part 2
+    pub fn english() -> Self {
+        Self::from_tables(&[ENGLISH])
+    }
+
+    /// One of the vocabularies in `BUILTIN`
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "english" => Some(Self::english()),
+            "english-extended" => Some(Self::from_tables(&[ENGLISH, ENGLISH_EXTENDED])),
+            "spanish" => Some(Self::from_tables(&[SPANISH])),
+            "german" => Some(Self::from_tables(&[GERMAN])),
+            _ => None,
+        }
+    }
+
+    fn from_tables(tables: &[&[(&str, u32)]]) -> Self {
+        Self::new(
+            tables
+                .iter()
+                .flat_map(|table| table.iter().map(|(word, value)| (word.to_string(), *value))),
+        )
+    }
+
+    /// Reads a vocabulary with one `word value` pair per line, skipping blank
+    /// lines and `#` comments
+    pub fn parse(text: &str) -> Result<Self, Located> {
+        let entries = solve_lines(text, |line| {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                return Ok(None);
+            }
+            let (word, value) = parse_all(
+                line,
+                separated_pair(take_till1(char::is_whitespace), space1, number),
+            )?;
+            Ok(Some((word.to_string(), value)))
+        })?;
+        Ok(Self::new(entries.into_iter().flatten()))
+    }
+
+    /// A built in vocabulary by name, otherwise one read from the file at `name`
+    pub fn load(name: &str) -> Result<Self, String> {
+        if let Some(vocabulary) = Self::builtin(name) {
+            return Ok(vocabulary);
+        }
+        let path = Path::new(name);
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        Self::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+}
+
+/// A digit or spelled out number found in a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digit {
+    pub value: u32,
+    /// Byte offsets of the match in the line
+    pub start: usize,
+    pub end: usize,
+    pub spelled: bool,
+}
+
+/// How a line's calibration value was recovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineReport {
+    /// 1-based
+    pub line: usize,
+    pub first: Digit,
+    pub last: Digit,
+    pub value: u128,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    NoDigits { line: usize },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoDigits { line } => write!(f, "line {}: no digits in the line", line),
+            Error::Io(err) => write!(f, "Failed to read the document: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// A pattern the scanner looks for
+#[derive(Debug, Clone, Copy)]
+struct Output {
+    len: usize,
+    value: u32,
+    spelled: bool,
+}
+
+/// An Aho-Corasick automaton over bytes, with every transition filled in so
+/// each byte is a single lookup
+struct Automaton {
+    next: Vec<[u32; 256]>,
+    /// The patterns that end in each state, longest first
+    outputs: Vec<Vec<Output>>,
+}
+
+impl Automaton {
+    fn new<'a>(patterns: impl Iterator<Item = (&'a [u8], u32, bool)>) -> Self {
+        let mut next = vec![[0u32; 256]];
+        let mut outputs = vec![Vec::new()];
+        for (pattern, value, spelled) in patterns {
+            let mut state = 0;
+            for &byte in pattern {
+                if next[state][byte as usize] == 0 {
+                    next.push([0; 256]);
+                    outputs.push(Vec::new());
+                    next[state][byte as usize] = (next.len() - 1) as u32;
+                }
+                state = next[state][byte as usize] as usize;
+            }
+            outputs[state].push(Output {
+                len: pattern.len(),
+                value,
+                spelled,
+            });
+        }
+        // Breadth first so a state's failure link is finished before the state
+        let mut fail = vec![0; next.len()];
+        let mut queue: VecDeque<usize> = next[0]
+            .iter()
+            .filter(|&&child| child != 0)
+            .map(|&child| child as usize)
+            .collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            outputs[state].sort_by_key(|output| Reverse(output.len));
+            let fallback = next[fail[state]];
+            for (byte, child) in next[state].iter_mut().enumerate() {
+                if *child == 0 {
+                    *child = fallback[byte];
+                } else {
+                    fail[*child as usize] = fallback[byte] as usize;
+                    queue.push_back(*child as usize);
+                }
+            }
+        }
+        Self { next, outputs }
+    }
+}
+
+/// Finds the first and last number in a line, overlapping words and all,
+/// without trying every word at every position
+///
+/// The first match comes from scanning forward until no later byte could
+/// start an earlier word, and the last from scanning backward with the words
+/// reversed, so both usually stop within a few bytes of the ends of the line.
+pub struct Scanner {
+    forward: Automaton,
+    backward: Automaton,
+    longest: usize,
+}
+
+impl Scanner {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let digits: Vec<(Vec<u8>, u32)> = (0..10)
+            .map(|digit| (digit.to_string().into_bytes(), digit))
+            .collect();
+        let patterns = || {
+            digits
+                .iter()
+                .map(|(digit, value)| (digit.as_slice(), *value, false))
+                .chain(
+                    vocabulary
+                        .words
+                        .iter()
+                        .map(|(word, value)| (word.as_bytes(), *value, true)),
+                )
+        };
+        let reversed: Vec<(Vec<u8>, u32, bool)> = patterns()
+            .map(|(pattern, value, spelled)| {
+                (pattern.iter().rev().copied().collect(), value, spelled)
+            })
+            .collect();
+        Self {
+            forward: Automaton::new(patterns()),
+            backward: Automaton::new(
+                reversed
+                    .iter()
+                    .map(|(pattern, value, spelled)| (pattern.as_slice(), *value, *spelled)),
+            ),
+            longest: patterns()
+                .map(|(pattern, _, _)| pattern.len())
+                .max()
+                .unwrap(),
+        }
+    }
+
+    /// The match that starts first, the longest if several start together
+    fn first(&self, line: &[u8]) -> Option<Digit> {
+        let mut state = 0;
+        let mut best: Option<Digit> = None;
+        for (idx, &byte) in line.iter().enumerate() {
+            if best.is_some_and(|best| idx >= best.start + self.longest) {
+                break;
+            }
+            state = self.forward.next[state][byte as usize] as usize;
+            for output in &self.forward.outputs[state] {
+                let start = idx + 1 - output.len;
+                if best.is_none_or(|best| {
+                    start < best.start || (start == best.start && idx + 1 > best.end)
+                }) {
+                    best = Some(Digit {
+                        value: output.value,
+                        start,
+                        end: idx + 1,
+                        spelled: output.spelled,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    /// The match that starts last, the longest if several start together
+    fn last(&self, line: &[u8]) -> Option<Digit> {
+        let mut state = 0;
+        for (idx, &byte) in line.iter().enumerate().rev() {
+            state = self.backward.next[state][byte as usize] as usize;
+            // Every word ending here in reverse starts at `idx`, longest first
+            if let Some(output) = self.backward.outputs[state].first() {
+                return Some(Digit {
+                    value: output.value,
+                    start: idx,
+                    end: idx + output.len,
+                    spelled: output.spelled,
+                });
+            }
+        }
+        None
+    }
+
+    /// The first and last number in the line, the same one if there's only one
+    pub fn digits(&self, line: &[u8]) -> Option<(Digit, Digit)> {
+        Some((self.first(line)?, self.last(line)?))
+    }
+
+    /// Reports on each line of a document as it's read, a byte at a time, so
+    /// no line is ever held in memory
+    pub fn reports<R: BufRead>(&self, reader: R) -> Reports<'_, R> {
+        Reports {
+            scanner: self,
+            reader,
+            line: 0,
+        }
+    }
+
+    /// Sums the calibration values of a document, stopping at the first line
+    /// without any digits
+    pub fn calibrate(&self, reader: impl BufRead) -> Result<u128, Error> {
+        self.reports(reader)
+            .map(|report| report.map(|report| report.value))
+            .sum()
+    }
+}
+
+fn join(first: u32, last: u32) -> u128 {
+    first as u128 * 10u128.pow(last.checked_ilog10().unwrap_or(0) + 1) + last as u128
+}
+
+/// The lines of a document read through a `Scanner`, see `Scanner::reports`
+pub struct Reports<'a, R> {
+    scanner: &'a Scanner,
+    reader: R,
+    line: usize,
+}
+
+/// The first and last match found so far in a line being read a chunk at a
+/// time
+///
+/// The forward automaton reports every match as it ends, so nothing but its
+/// state and the two best matches has to be kept, however long the line.
+#[derive(Default)]
+struct LineScan {
+    state: usize,
+    /// Bytes of the line seen so far
+    len: usize,
+    first: Option<Digit>,
+    last: Option<Digit>,
+}
+
+impl LineScan {
+    fn push(&mut self, automaton: &Automaton, byte: u8) {
+        self.len += 1;
+        self.state = automaton.next[self.state][byte as usize] as usize;
+        for output in &automaton.outputs[self.state] {
+            let digit = Digit {
+                value: output.value,
+                start: self.len - output.len,
+                end: self.len,
+                spelled: output.spelled,
+            };
+            // A match ending later than an earlier one with the same start is
+            // the longer of the two
+            if self.first.is_none_or(|first| {
+                digit.start < first.start || (digit.start == first.start && digit.end > first.end)
+            }) {
+                self.first = Some(digit);
+            }
+            if self.last.is_none_or(|last| digit.start >= last.start) {
+                self.last = Some(digit);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Reports<'_, R> {
+    type Item = Result<LineReport, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut scan = LineScan::default();
+        let mut read = false;
+        loop {
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Some(Err(err.into())),
+            };
+            if chunk.is_empty() {
+                break;
+            }
+            read = true;
+            let (len, done) = match chunk.iter().position(|&byte| byte == b'\n') {
+                Some(end) => (end + 1, true),
+                None => (chunk.len(), false),
+            };
+            chunk[..len]
+                .iter()
+                .for_each(|&byte| scan.push(&self.scanner.forward, byte));
+            self.reader.consume(len);
+            if done {
+                break;
+            }
+        }
+        if !read {
+            return None;
+        }
+        self.line += 1;
+        let line = self.line;
+        let report = match scan.first.zip(scan.last) {
+            Some((first, last)) => Ok(LineReport {
+                line,
+                first,
+                last,
+                value: join(first.value, last.value),
+            }),
+            None => Err(Error::NoDigits { line }),
+        };
+        Some(report)
+    }
+}
+
+/// The first number in the line followed by the last, as written
+///
+/// With numbers past nine that's the two written side by side, so `twelve`
+/// then `three` is 123. A line without any digits is line 1 in the error.
+pub fn calibration_value(line: &str, scanner: &Scanner) -> Result<u128, Error> {
+    let (first, last) = scanner
+        .digits(line.as_bytes())
+        .ok_or(Error::NoDigits { line: 1 })?;
+    Ok(join(first.value, last.value))
+}
+
+/// Reports on every line of the text, see `Scanner::reports`
+pub fn report(text: &str, vocabulary: &Vocabulary) -> Vec<Result<LineReport, Error>> {
+    Scanner::new(vocabulary).reports(text.as_bytes()).collect()
+}
+
+pub fn part1(text: String) -> Result<u128, Error> {
+    Scanner::new(&Vocabulary::digits()).calibrate(text.as_bytes())
+}
+
+pub fn part2(text: String) -> Result<u128, Error> {
+    Scanner::new(&Vocabulary::english()).calibrate(text.as_bytes())
 }
@@ -1,12 +1,67 @@
-use day1::{part1, part2};
-use std::fs;
+use day1::{part1, part2, Digit, Error, Scanner, Vocabulary};
+use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
 
 fn main() {
-    let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text));
+    // `--vocabulary NAME|PATH` calibrates with other number words instead, and
+    // `--report` shows how each line's value was found
+    let mut vocabulary = None;
+    let mut report = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vocabulary" => {
+                let name = args.next().expect("Failed to find a vocabulary name");
+                vocabulary = Some(Vocabulary::load(&name).expect("Failed to load the vocabulary"));
+            }
+            "--report" => report = true,
+            _ => panic!("Failed to understand argument {}", arg),
+        }
+    }
+
+    if vocabulary.is_none() && !report {
+        let text = fs::read_to_string("data/input.txt").unwrap();
+        println!("{}", part1(text).unwrap());
+
+        let text = fs::read_to_string("data/input.txt").unwrap();
+        println!("{}", part2(text).unwrap());
+        return;
+    }
 
-    let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part2(text));
+    let scanner = Scanner::new(&vocabulary.unwrap_or_else(Vocabulary::english));
+    let input = BufReader::new(File::open("data/input.txt").unwrap());
+    if !report {
+        println!("{}", scanner.calibrate(input).unwrap());
+        return;
+    }
+    let mut total: u128 = 0;
+    for line in scanner.reports(input) {
+        match line {
+            Ok(line) => {
+                let describe = |digit: Digit| {
+                    format!(
+                        "{} {} at {}..{}",
+                        if digit.spelled { "spelled" } else { "numeric" },
+                        digit.value,
+                        digit.start,
+                        digit.end
+                    )
+                };
+                println!(
+                    "line {}: {}, {} => {}",
+                    line.line,
+                    describe(line.first),
+                    describe(line.last),
+                    line.value
+                );
+                total += line.value;
+            }
+            Err(Error::Io(err)) => panic!("Failed to read input: {}", err),
+            Err(err) => println!("{}", err),
+        }
+    }
+    println!("{}", total);
 }
 
 #[cfg(test)]
@@ -16,12 +71,98 @@ mod tests {
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
-        assert_eq!(part1(text), 142)
+        assert_eq!(part1(text).unwrap(), 142)
     }
 
     #[test]
     fn example2() {
         let text = fs::read_to_string("data/part2_example.txt").unwrap();
-        assert_eq!(part2(text), 281)
+        assert_eq!(part2(text).unwrap(), 281)
+    }
+
+    #[test]
+    fn vocabularies() {
+        let english = Scanner::new(&Vocabulary::english());
+        assert_eq!(day1::calibration_value("eightwo", &english).unwrap(), 82);
+        assert_eq!(
+            day1::calibration_value("xtwone3four", &english).unwrap(),
+            24
+        );
+        assert!(matches!(
+            day1::calibration_value("abc", &english),
+            Err(Error::NoDigits { line: 1 })
+        ));
+        let extended = Scanner::new(&Vocabulary::builtin("english-extended").unwrap());
+        assert_eq!(
+            day1::calibration_value("seventeenine", &extended).unwrap(),
+            179
+        );
+        let spanish = Scanner::new(&Vocabulary::builtin("spanish").unwrap());
+        assert_eq!(
+            day1::calibration_value("dosxveintetres", &spanish).unwrap(),
+            23
+        );
+        let german = Scanner::new(&Vocabulary::builtin("german").unwrap());
+        assert_eq!(day1::calibration_value("fünfzwölf", &german).unwrap(), 512);
+        let custom = Scanner::new(&Vocabulary::parse("# roman numerals\nIV 4\n\nIX 9\n").unwrap());
+        assert_eq!(day1::calibration_value("xIVyIX", &custom).unwrap(), 49);
+        let err = Vocabulary::parse("IV 4\nIX nine\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+    }
+
+    #[test]
+    fn streams() {
+        let scanner = Scanner::new(&Vocabulary::english());
+        let input = BufReader::new(File::open("data/part2_example.txt").unwrap());
+        assert_eq!(scanner.calibrate(input).unwrap(), 281);
+        let text = fs::read_to_string("data/input.txt").unwrap();
+        let input = BufReader::new(File::open("data/input.txt").unwrap());
+        assert_eq!(scanner.calibrate(input).unwrap(), part2(text).unwrap());
+        assert_eq!(
+            scanner
+                .calibrate("oneight\nsevenineteen".as_bytes())
+                .unwrap(),
+            18 + 79
+        );
+        // Chunks smaller than a word still find matches across their edges
+        let input = BufReader::with_capacity(3, "xtwone3four\neightwo\nsevenine".as_bytes());
+        assert_eq!(scanner.calibrate(input).unwrap(), 24 + 82 + 79);
+        let text = fs::read_to_string("data/input.txt").unwrap();
+        for (line, report) in text.lines().zip(scanner.reports(text.as_bytes())) {
+            let report = report.unwrap();
+            assert_eq!(
+                scanner.digits(line.as_bytes()),
+                Some((report.first, report.last))
+            );
+        }
+        let err = scanner.calibrate("1\nabc\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: no digits in the line");
+    }
+
+    #[test]
+    fn reports() {
+        let lines = day1::report("a1b\nxeightwo\nabc\n", &Vocabulary::english());
+        let first = lines[0].as_ref().unwrap();
+        assert_eq!((first.first, first.value), (first.last, 11));
+        assert_eq!(
+            first.first,
+            Digit {
+                value: 1,
+                start: 1,
+                end: 2,
+                spelled: false,
+            }
+        );
+        let second = lines[1].as_ref().unwrap();
+        assert_eq!((second.first.start, second.first.end), (1, 6));
+        assert_eq!((second.last.start, second.last.end), (5, 8));
+        assert!(second.first.spelled && second.last.spelled);
+        assert_eq!(second.value, 82);
+        assert!(matches!(lines[2], Err(Error::NoDigits { line: 3 })));
+        let huge = Scanner::new(&Vocabulary::parse("big 4294967295\n").unwrap());
+        assert_eq!(
+            day1::calibration_value("bigbig", &huge).unwrap(),
+            42949672954294967295
+        );
     }
 }
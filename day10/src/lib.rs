@@ -1,9 +1,9 @@
-#![feature(hash_extract_if)]
 use std::collections::HashSet;
 
 use colored::Colorize;
 
 use glam::{IVec2, UVec2};
+use parsing::{char_grid, parse_all};
 
 /// Direction we are traversing through pipe
 /// CW = Clockwise, meaning the outside tiles are on the left
@@ -229,23 +229,32 @@ fn parse_grid(text: String) -> Grid {
         start: Tile::Start(UVec2::new(0, 0)),
         tiles: Vec::new(),
     };
-    for (i, line) in text.lines().enumerate() {
+    // Each character maps to the tile constructor, which needs the tile's location
+    let tiles = parse_all(
+        text.as_str(),
+        char_grid(|c| -> Option<fn(UVec2) -> Tile> {
+            match c {
+                '|' => Some(Tile::NS),
+                '-' => Some(Tile::EW),
+                'L' => Some(Tile::NE),
+                'J' => Some(Tile::NW),
+                '7' => Some(Tile::SW),
+                'F' => Some(Tile::SE),
+                '.' => Some(Tile::Ground),
+                'S' => Some(Tile::Start),
+                _ => None,
+            }
+        }),
+    )
+    .unwrap();
+    for (i, line) in tiles.into_rows().into_iter().enumerate() {
         let mut row = Vec::new();
-        for (j, c) in line.chars().enumerate() {
-            row.push(match c {
-                '|' => Tile::NS(UVec2::new(j as u32, i as u32)),
-                '-' => Tile::EW(UVec2::new(j as u32, i as u32)),
-                'L' => Tile::NE(UVec2::new(j as u32, i as u32)),
-                'J' => Tile::NW(UVec2::new(j as u32, i as u32)),
-                '7' => Tile::SW(UVec2::new(j as u32, i as u32)),
-                'F' => Tile::SE(UVec2::new(j as u32, i as u32)),
-                '.' => Tile::Ground(UVec2::new(j as u32, i as u32)),
-                'S' => {
-                    grid.start = Tile::Start(UVec2::new(j as u32, i as u32));
-                    Tile::Start(UVec2::new(j as u32, i as u32))
-                }
-                _ => panic!("Parsed bad character"),
-            });
+        for (j, tile) in line.into_iter().enumerate() {
+            let tile = tile(UVec2::new(j as u32, i as u32));
+            if let Tile::Start(_) = tile {
+                grid.start = tile;
+            }
+            row.push(tile);
         }
         grid.tiles.push(row);
     }
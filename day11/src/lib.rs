@@ -2,6 +2,7 @@ use core::fmt;
 use std::collections::HashSet;
 
 use glam::U64Vec2;
+use parsing::{char_grid, parse_all};
 
 #[derive(Debug)]
 struct Grid {
@@ -27,20 +28,22 @@ impl fmt::Display for Grid {
 }
 
 fn parse_grid(text: String) -> Grid {
-    let mut galaxies = HashSet::new();
-    for (i, row) in text.lines().enumerate() {
-        for (j, c) in row.chars().enumerate() {
-            if c == '#' {
-                galaxies.insert(U64Vec2::new(j as u64, i as u64));
-            }
-        }
-    }
+    let image = parse_all(
+        text.as_str(),
+        char_grid(|c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }),
+    )
+    .unwrap();
     Grid {
-        bounds: U64Vec2::new(
-            text.lines().next().unwrap().len() as u64,
-            text.lines().count() as u64,
-        ),
-        galaxies,
+        bounds: U64Vec2::new(image.width as u64, image.height as u64),
+        galaxies: image
+            .iter()
+            .filter(|(_, _, &galaxy)| galaxy)
+            .map(|(x, y, _)| U64Vec2::new(x as u64, y as u64))
+            .collect(),
     }
 }
 
@@ -81,21 +84,22 @@ fn dist(g1: &U64Vec2, g2: &U64Vec2) -> u64 {
     g1.x.abs_diff(g2.x) + g1.y.abs_diff(g2.y)
 }
 
+/// How many times bigger each empty row and column gets in part 2
+pub const PART2_EXPANSION: u64 = 1000000;
+
 pub fn part1(text: String) -> u64 {
-    let grid = parse_grid(text);
-    let grid = expand(grid, 2);
-    let mut sum = 0;
-    for galaxy1 in grid.galaxies.iter() {
-        for galaxy2 in grid.galaxies.iter() {
-            sum += dist(galaxy1, galaxy2);
-        }
-    }
-    sum / 2
+    sum_of_distances(text, 2)
 }
 
 pub fn part2(text: String) -> u64 {
+    sum_of_distances(text, PART2_EXPANSION)
+}
+
+/// Sums the distances between every pair of galaxies once each empty row and
+/// column has been made `expansion_ratio` times bigger
+pub fn sum_of_distances(text: String, expansion_ratio: u64) -> u64 {
     let grid = parse_grid(text);
-    let grid = expand(grid, 1000000);
+    let grid = expand(grid, expansion_ratio);
     let mut sum = 0;
     for galaxy1 in grid.galaxies.iter() {
         for galaxy2 in grid.galaxies.iter() {
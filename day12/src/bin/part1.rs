@@ -1,18 +1,25 @@
-use std::fs;
 use day12::part1;
+use std::fs;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text));
+    println!("{}", part1(text).unwrap());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day12::part1_dynamic;
 
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
-        assert_eq!(part1(text), 21)
+        assert_eq!(part1(text).unwrap(), 21)
+    }
+
+    #[test]
+    fn part1_dynamic_agrees() {
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        assert_eq!(part1_dynamic(text).unwrap(), 21)
     }
 }
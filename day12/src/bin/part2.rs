@@ -3,7 +3,7 @@ use day12::part2;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part2(text));
+    println!("{}", part2(text).unwrap());
 }
 
 #[cfg(test)]
@@ -13,6 +13,6 @@ mod tests {
     #[test]
     fn example2() {
         let text = fs::read_to_string("data/part2_example.txt").unwrap();
-        assert_eq!(part2(text), 525152)
+        assert_eq!(part2(text).unwrap(), 525152)
     }
 }
@@ -9,8 +9,8 @@ use nom::{
     combinator::map,
     multi::{many1, separated_list1},
     sequence::separated_pair,
-    IResult,
 };
+use parsing::{parse_all, solve_lines, IResult, Located};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
 enum Spring {
@@ -80,7 +80,7 @@ fn is_valid(springs: &[Spring], nums: &[usize]) -> bool {
     zip(nums.iter(), damaged_slices.iter()).all(|(&num, damaged_slice)| num == damaged_slice.len())
 }
 
-fn parse_line(text: &str) -> IResult<&str, Row> {
+fn parse_line(text: &str) -> IResult<'_, Row> {
     let (text, (springs, nums)) = separated_pair(
         many1(map(alt((char('.'), char('#'), char('?'))), |c| match c {
             '.' => Spring::Operational,
@@ -95,10 +95,10 @@ fn parse_line(text: &str) -> IResult<&str, Row> {
 }
 
 // Very much, brute forcing it
-pub fn part1(text: String) -> u32 {
-    let mut valid_arrangements = 0;
-    for line in text.lines() {
-        let (_, row) = parse_line(line).unwrap();
+pub fn part1(text: String) -> Result<u32, Located> {
+    let arrangements = solve_lines(&text, |line| {
+        let row = parse_all(line, parse_line)?;
+        let mut valid_arrangements = 0;
         let combos = repeat_n(
             [Spring::Operational, Spring::Damaged].into_iter(),
             row.springs
@@ -120,8 +120,15 @@ pub fn part1(text: String) -> u32 {
                 valid_arrangements += 1;
             }
         }
-    }
-    valid_arrangements
+        Ok(valid_arrangements)
+    })?;
+    Ok(arrangements.iter().sum())
+}
+
+/// Part 1 again, but with the dynamic programming from part 2 instead of brute force
+pub fn part1_dynamic(text: String) -> Result<usize, Located> {
+    let arrangements = solve_lines(&text, |line| Ok(num_valid(parse_all(line, parse_line)?)))?;
+    Ok(arrangements.iter().sum())
 }
 
 fn expand(row: Row) -> Row {
@@ -205,12 +212,10 @@ fn num_valid(row: Row) -> usize {
         .sum()
 }
 
-pub fn part2(text: String) -> usize {
-    text.lines()
-        .map(|line| {
-            let (_, row) = parse_line(line).unwrap();
-            let row = expand(row);
-            num_valid(row)
-        })
-        .sum()
+pub fn part2(text: String) -> Result<usize, Located> {
+    let arrangements = solve_lines(&text, |line| {
+        let row = parse_all(line, parse_line)?;
+        Ok(num_valid(expand(row)))
+    })?;
+    Ok(arrangements.iter().sum())
 }
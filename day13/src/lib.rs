@@ -1,6 +1,8 @@
 use std::iter::zip;
 
 use grid::Grid;
+use nom::{character::complete::line_ending, multi::separated_list1, sequence::pair};
+use parsing::{char_grid, parse_all};
 
 #[derive(Debug, PartialEq, Clone)]
 enum Tile {
@@ -8,18 +10,23 @@ enum Tile {
     Rock,
 }
 
-fn parse_grid(text: &str) -> Grid<Tile> {
-    Grid::from_vec(
-        text.replace('\n', "")
-            .chars()
-            .map(|c| match c {
-                '.' => Tile::Ash,
-                '#' => Tile::Rock,
-                _ => panic!(),
-            })
-            .collect::<Vec<Tile>>(),
-        text.lines().next().unwrap().len(),
+fn parse_grids(text: &str) -> Vec<Grid<Tile>> {
+    let patterns = parse_all(
+        text,
+        separated_list1(
+            pair(line_ending, line_ending),
+            char_grid(|c| match c {
+                '.' => Some(Tile::Ash),
+                '#' => Some(Tile::Rock),
+                _ => None,
+            }),
+        ),
     )
+    .unwrap();
+    patterns
+        .into_iter()
+        .map(|pattern| Grid::from_vec(pattern.cells, pattern.width))
+        .collect()
 }
 
 /// Finds each vertical and horizontal symmetry and returns the correct solution calculation for
@@ -56,18 +63,16 @@ fn get_symmetries(grid: &Grid<Tile>, ignored_symmetry: usize) -> usize {
 }
 
 pub fn part1(text: String) -> usize {
-    text.split("\n\n")
-        .map(|grid_text| {
-            let grid = parse_grid(grid_text);
-            get_symmetries(&grid, 0)
-        })
+    parse_grids(text.as_str())
+        .into_iter()
+        .map(|grid| get_symmetries(&grid, 0))
         .sum()
 }
 
 pub fn part2(text: String) -> usize {
-    text.split("\n\n")
-        .map(|grid_text| {
-            let mut grid = parse_grid(grid_text);
+    parse_grids(text.as_str())
+        .into_iter()
+        .map(|mut grid| {
             let orig_symmetries = get_symmetries(&grid, 0);
             for i in 0..grid.rows() {
                 for j in 0..grid.cols() {
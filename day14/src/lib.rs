@@ -1,9 +1,9 @@
-use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 
+use common::simulation::{find_cycle, Simulation};
 use grid::Grid;
-use indicatif::ProgressIterator;
+use parsing::{char_grid, parse_all};
 
 #[derive(PartialEq, Eq, Clone)]
 struct HashableGrid<T: Eq> {
@@ -46,18 +46,18 @@ impl Debug for Tile {
 }
 
 fn parse_grid(text: String) -> HashableGrid<Tile> {
+    let platform = parse_all(
+        text.as_str(),
+        char_grid(|c| match c {
+            'O' => Some(Tile::Round),
+            '#' => Some(Tile::Cube),
+            '.' => Some(Tile::Empty),
+            _ => None,
+        }),
+    )
+    .unwrap();
     HashableGrid {
-        grid: Grid::from_vec(
-            text.chars()
-                .filter_map(|c| match c {
-                    'O' => Some(Tile::Round),
-                    '#' => Some(Tile::Cube),
-                    '.' => Some(Tile::Empty),
-                    _ => None,
-                })
-                .collect::<Vec<Tile>>(),
-            text.lines().next().unwrap().len(),
-        ),
+        grid: Grid::from_vec(platform.cells, platform.width),
     }
 }
 
@@ -193,24 +193,47 @@ fn east(grid: HashableGrid<Tile>) -> HashableGrid<Tile> {
     HashableGrid { grid: new_grid }
 }
 
-pub fn part2(text: String) -> usize {
-    let mut grid = parse_grid(text);
-    let mut state_cache: HashMap<HashableGrid<Tile>, i32> = HashMap::new();
-    let num_cycles = 1000000000;
-    for i in (0..num_cycles).progress() {
-        grid = north(grid);
-        grid = west(grid);
-        grid = south(grid);
-        grid = east(grid);
-        if state_cache.contains_key(&grid) {
-            let first_idx = state_cache.get(&grid).unwrap();
-            let cycle_len = i - first_idx;
-            grid = state_cache.iter().find(|(_,&v)| {
-                v == first_idx + ((num_cycles - first_idx - 1) % cycle_len)
-            }).expect("Didn't find the grid").0.clone();
-            break;
+/// The platform being spun, one north, west, south, east cycle per step
+#[derive(PartialEq, Eq, Clone, Hash, Debug)]
+pub struct Platform {
+    grid: HashableGrid<Tile>,
+}
+
+impl Platform {
+    pub fn new(text: String) -> Self {
+        Self {
+            grid: parse_grid(text),
         }
-        state_cache.insert(grid.clone(), i);
     }
-    calc_load(&grid)
+
+    pub fn load(&self) -> usize {
+        calc_load(&self.grid)
+    }
+}
+
+impl Simulation for Platform {
+    type Snapshot = Platform;
+
+    fn step(&mut self) -> bool {
+        let grid = self.grid.clone();
+        self.grid = east(south(west(north(grid))));
+        true
+    }
+
+    fn snapshot(&self) -> Platform {
+        self.clone()
+    }
+}
+
+pub const PART2_CYCLES: usize = 1000000000;
+
+pub fn part2(text: String) -> usize {
+    load_after_cycles(text, PART2_CYCLES)
+}
+
+/// The load on the north beams after spinning the platform `num_cycles` times
+pub fn load_after_cycles(text: String, num_cycles: usize) -> usize {
+    let mut platform = Platform::new(text);
+    let cycle = find_cycle(&mut platform).expect("Spinning never settles into a cycle");
+    cycle.state_at(num_cycles).load()
 }
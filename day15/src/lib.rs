@@ -2,12 +2,12 @@ use std::collections::HashMap;
 
 use nom::{
     branch::alt,
-    character::complete::{alpha1, char, digit0},
-    combinator::map,
+    character::complete::{alpha1, char},
+    combinator::{map, value},
     multi::separated_list1,
-    sequence::pair,
-    IResult,
+    sequence::{pair, preceded},
 };
+use parsing::{number, parse_all, IResult};
 
 fn custom_hash(input: &str) -> u32 {
     input
@@ -31,28 +31,28 @@ struct Lens<'a> {
     focal_len: u32,
 }
 
-fn parse_ops(text: &str) -> IResult<&str, Vec<LensOp>> {
+/// `label-` or `label=focal_len`
+fn parse_ops(text: &str) -> IResult<'_, Vec<LensOp<'_>>> {
     separated_list1(
         char(','),
         map(
-            pair(alpha1, pair(alt((char('-'), char('='))), digit0)),
-            |(label, (op, lens))| match op {
-                '-' => LensOp::Remove(Lens {
+            pair(
+                alpha1,
+                alt((value(None, char('-')), map(preceded(char('='), number), Some))),
+            ),
+            |(label, focal_len)| match focal_len {
+                None => LensOp::Remove(Lens {
                     label,
                     focal_len: 0,
                 }),
-                '=' => LensOp::Add(Lens {
-                    label,
-                    focal_len: lens.chars().last().unwrap().to_digit(10).unwrap(),
-                }),
-                _ => panic!("failed to parse"),
+                Some(focal_len) => LensOp::Add(Lens { label, focal_len }),
             },
         ),
     )(text)
 }
 
 pub fn part2(text: String) -> u32 {
-    let (_, ops) = parse_ops(text.as_str()).unwrap();
+    let ops = parse_all(text.as_str(), parse_ops).expect("Failed to parse.");
     let mut boxes: HashMap<u32, Vec<Lens>> = HashMap::new();
     for op in ops {
         match op {
@@ -1,9 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
+use common::simulation::Simulation;
 use glam::IVec2;
+use parsing::{char_grid, parse_all};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     North,
     South,
     East,
@@ -19,47 +21,73 @@ enum Tile {
     VerticalSplitter,
 }
 
-struct Grid {
+pub struct Grid {
     bounds: IVec2,
     tiles: HashMap<IVec2, Tile>,
 }
 
-fn parse_grid(text: String) -> Grid {
-    let mut tiles = HashMap::new();
-    for (y, row) in text.lines().enumerate() {
-        for (x, c) in row.chars().enumerate() {
-            tiles.insert(
-                IVec2::new(x as i32, y as i32),
-                match c {
-                    '.' => Tile::Empty,
-                    '/' => Tile::ForwardMirror,
-                    '\\' => Tile::BackwardMirror,
-                    '-' => Tile::HorizontalSplitter,
-                    '|' => Tile::VerticalSplitter,
-                    _ => panic!("Failed to parse"),
-                },
-            );
-        }
-    }
+pub fn parse_grid(text: String) -> Grid {
+    let contraption = parse_all(
+        text.as_str(),
+        char_grid(|c| match c {
+            '.' => Some(Tile::Empty),
+            '/' => Some(Tile::ForwardMirror),
+            '\\' => Some(Tile::BackwardMirror),
+            '-' => Some(Tile::HorizontalSplitter),
+            '|' => Some(Tile::VerticalSplitter),
+            _ => None,
+        }),
+    )
+    .unwrap();
     Grid {
-        bounds: IVec2::new(
-            text.lines().next().unwrap().len() as i32,
-            text.lines().count() as i32,
-        ),
-        tiles,
+        bounds: IVec2::new(contraption.width as i32, contraption.height as i32),
+        tiles: contraption
+            .cells
+            .into_iter()
+            .enumerate()
+            .map(|(idx, tile)| {
+                let x = idx % contraption.width;
+                let y = idx / contraption.width;
+                (IVec2::new(x as i32, y as i32), tile)
+            })
+            .collect(),
     }
 }
 
-fn count_energized(grid: &Grid, start: (IVec2, Direction)) -> usize {
+/// A beam of light bouncing around the grid, following one beam head per step
+pub struct Beam<'a> {
+    grid: &'a Grid,
     // Store where the beam has been and what direction it was going when it entered
-    let mut beam_path: HashMap<IVec2, HashSet<Direction>> = HashMap::new();
-    let mut beam_heads = Vec::new();
-    beam_heads.push(start);
-    while let Some((loc, dir)) = beam_heads.pop() {
-        if beam_path.contains_key(&loc) && beam_path.get(&loc).unwrap().contains(&dir) {
-            continue;
+    beam_path: HashMap<IVec2, HashSet<Direction>>,
+    beam_heads: Vec<(IVec2, Direction)>,
+}
+
+impl<'a> Beam<'a> {
+    pub fn new(grid: &'a Grid, loc: IVec2, dir: Direction) -> Self {
+        Self {
+            grid,
+            beam_path: HashMap::new(),
+            beam_heads: vec![(loc, dir)],
         }
-        match grid.tiles.get(&loc) {
+    }
+
+    pub fn energized(&self) -> usize {
+        self.beam_path.len()
+    }
+}
+
+impl Simulation for Beam<'_> {
+    type Snapshot = HashSet<IVec2>;
+
+    fn step(&mut self) -> bool {
+        let Some((loc, dir)) = self.beam_heads.pop() else {
+            return false;
+        };
+        let beam_heads = &mut self.beam_heads;
+        if self.beam_path.contains_key(&loc) && self.beam_path.get(&loc).unwrap().contains(&dir) {
+            return true;
+        }
+        match self.grid.tiles.get(&loc) {
             Some(Tile::Empty) => beam_heads.push(match dir {
                 Direction::North => (loc - IVec2::new(0, 1), Direction::North),
                 Direction::South => (loc + IVec2::new(0, 1), Direction::South),
@@ -97,12 +125,23 @@ fn count_energized(grid: &Grid, start: (IVec2, Direction)) -> usize {
             },
 
             None => {
-                continue;
+                return true;
             }
         }
-        beam_path.entry(loc).or_default().insert(dir);
+        self.beam_path.entry(loc).or_default().insert(dir);
+        true
+    }
+
+    /// The energized tiles
+    fn snapshot(&self) -> HashSet<IVec2> {
+        self.beam_path.keys().copied().collect()
     }
-    beam_path.len()
+}
+
+fn count_energized(grid: &Grid, start: (IVec2, Direction)) -> usize {
+    let mut beam = Beam::new(grid, start.0, start.1);
+    beam.run_until(|_| false);
+    beam.energized()
 }
 
 pub fn part1(text: String) -> usize {
@@ -1,7 +1,8 @@
-use pathfinding::prelude::dijkstra;
+use common::search::{astar, SearchProblem};
 use std::collections::HashMap;
 
 use glam::IVec2;
+use parsing::{char_grid, parse_all};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum Direction {
@@ -24,289 +25,122 @@ struct State {
 }
 
 fn parse_grid(text: String) -> Grid {
-    let mut blocks = HashMap::new();
-    for (y, row) in text.lines().enumerate() {
-        for (x, c) in row.chars().enumerate() {
-            blocks.insert(IVec2::new(x as i32, y as i32), c.to_digit(10).unwrap());
+    let city = parse_all(text.as_str(), char_grid(|c| c.to_digit(10))).unwrap();
+    Grid {
+        bounds: IVec2::new(city.width as i32, city.height as i32),
+        blocks: city
+            .iter()
+            .map(|(x, y, &heat_loss)| (IVec2::new(x as i32, y as i32), heat_loss))
+            .collect(),
+    }
+}
+
+impl Direction {
+    fn offset(&self) -> IVec2 {
+        match self {
+            Direction::North => IVec2::new(0, -1),
+            Direction::South => IVec2::new(0, 1),
+            Direction::East => IVec2::new(1, 0),
+            Direction::West => IVec2::new(-1, 0),
         }
     }
-    Grid {
-        bounds: IVec2::new(
-            text.lines().next().unwrap().len() as i32,
-            text.lines().count() as i32,
-        ),
-        blocks,
+
+    fn turns(&self) -> [Direction; 2] {
+        match self {
+            Direction::North | Direction::South => [Direction::West, Direction::East],
+            Direction::East | Direction::West => [Direction::North, Direction::South],
+        }
     }
 }
 
-pub fn part1(text: String) -> u32 {
-    let grid = parse_grid(text);
-    let (_path, total_heat_loss) = dijkstra(
-        &State {
+/// Moving a crucible from the top left to the bottom right of the grid
+///
+/// The crucible has to go straight for more than `min_before_turn` blocks
+/// before it can turn or stop, and can't go straight for more than
+/// `max_consecutive` blocks after the first.
+struct Crucible {
+    grid: Grid,
+    min_before_turn: Option<i32>,
+    max_consecutive: i32,
+}
+
+impl Crucible {
+    fn can_turn(&self, s: &State) -> bool {
+        self.min_before_turn
+            .is_none_or(|min| s.consecutive_blocks >= min)
+    }
+}
+
+impl SearchProblem for Crucible {
+    type State = State;
+
+    fn start(&self) -> State {
+        State {
             loc: IVec2::new(0, 0),
             dir: Direction::East,
             consecutive_blocks: -1,
-        },
-        |s| {
-            let mut successors = Vec::new();
-            match s.dir {
-                Direction::North => {
-                    let next = State {
-                        loc: s.loc - IVec2::new(1, 0),
-                        dir: Direction::West,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    let next = State {
-                        loc: s.loc + IVec2::new(1, 0),
-                        dir: Direction::East,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    if s.consecutive_blocks < 2 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(0, 1),
-                            dir: Direction::North,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-                Direction::South => {
-                    let next = State {
-                        loc: s.loc - IVec2::new(1, 0),
-                        dir: Direction::West,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    let next = State {
-                        loc: s.loc + IVec2::new(1, 0),
-                        dir: Direction::East,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    if s.consecutive_blocks < 2 {
-                        let next = State {
-                            loc: s.loc + IVec2::new(0, 1),
-                            dir: Direction::South,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-                Direction::East => {
-                    let next = State {
-                        loc: s.loc - IVec2::new(0, 1),
-                        dir: Direction::North,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    let next = State {
-                        loc: s.loc + IVec2::new(0, 1),
-                        dir: Direction::South,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    if s.consecutive_blocks < 2 {
-                        let next = State {
-                            loc: s.loc + IVec2::new(1, 0),
-                            dir: Direction::East,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-                Direction::West => {
-                    let next = State {
-                        loc: s.loc - IVec2::new(0, 1),
-                        dir: Direction::North,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    let next = State {
-                        loc: s.loc + IVec2::new(0, 1),
-                        dir: Direction::South,
-                        consecutive_blocks: 0,
-                    };
-                    if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                        successors.push((next, *heat_loss));
-                    }
-                    if s.consecutive_blocks < 2 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(1, 0),
-                            dir: Direction::West,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
+        }
+    }
+
+    fn successors(&self, s: &State) -> Vec<(State, u64)> {
+        let mut successors = Vec::new();
+        if self.can_turn(s) {
+            for dir in s.dir.turns() {
+                successors.push(State {
+                    loc: s.loc + dir.offset(),
+                    dir,
+                    consecutive_blocks: 0,
+                });
             }
-            successors
-        },
-        |s| s.loc == grid.bounds - IVec2::new(1, 1),
-    )
-    .unwrap();
-    total_heat_loss
+        }
+        if s.consecutive_blocks < self.max_consecutive {
+            successors.push(State {
+                loc: s.loc + s.dir.offset(),
+                dir: s.dir.clone(),
+                consecutive_blocks: s.consecutive_blocks + 1,
+            });
+        }
+        successors
+            .into_iter()
+            .filter_map(|next| {
+                let heat_loss = *self.grid.blocks.get(&next.loc)?;
+                Some((next, heat_loss as u64))
+            })
+            .collect()
+    }
+
+    fn is_goal(&self, s: &State) -> bool {
+        s.loc == self.grid.bounds - IVec2::new(1, 1) && self.can_turn(s)
+    }
+
+    /// Every block loses at least one heat so the distance to the corner
+    /// never overestimates
+    fn heuristic(&self, s: &State) -> u64 {
+        let to_goal = self.grid.bounds - IVec2::new(1, 1) - s.loc;
+        (to_goal.x.abs() + to_goal.y.abs()) as u64
+    }
+}
+
+/// Blocks the crucible can go straight for after the first in part 1
+pub const PART1_MAX_CONSECUTIVE: i32 = 2;
+/// Blocks the ultra crucible has to go straight for before turning, and the
+/// most it can go straight for after the first, in part 2
+pub const PART2_MIN_BEFORE_TURN: i32 = 3;
+pub const PART2_MAX_CONSECUTIVE: i32 = 9;
+
+pub fn part1(text: String) -> u32 {
+    min_heat_loss(text, None, PART1_MAX_CONSECUTIVE)
 }
 
 pub fn part2(text: String) -> u32 {
-    let grid = parse_grid(text);
-    let (_path, total_heat_loss) = dijkstra(
-        &State {
-            loc: IVec2::new(0, 0),
-            dir: Direction::East,
-            consecutive_blocks: -1,
-        },
-        |s| {
-            let mut successors = Vec::new();
-            match s.dir {
-                Direction::North => {
-                    if s.consecutive_blocks >= 3 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(1, 0),
-                            dir: Direction::West,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                        let next = State {
-                            loc: s.loc + IVec2::new(1, 0),
-                            dir: Direction::East,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                    if s.consecutive_blocks < 9 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(0, 1),
-                            dir: Direction::North,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-                Direction::South => {
-                    if s.consecutive_blocks >= 3 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(1, 0),
-                            dir: Direction::West,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                        let next = State {
-                            loc: s.loc + IVec2::new(1, 0),
-                            dir: Direction::East,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                    if s.consecutive_blocks < 9 {
-                        let next = State {
-                            loc: s.loc + IVec2::new(0, 1),
-                            dir: Direction::South,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-                Direction::East => {
-                    if s.consecutive_blocks >= 3 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(0, 1),
-                            dir: Direction::North,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                        let next = State {
-                            loc: s.loc + IVec2::new(0, 1),
-                            dir: Direction::South,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                    if s.consecutive_blocks < 9 {
-                        let next = State {
-                            loc: s.loc + IVec2::new(1, 0),
-                            dir: Direction::East,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-                Direction::West => {
-                    if s.consecutive_blocks >= 3 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(0, 1),
-                            dir: Direction::North,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                        let next = State {
-                            loc: s.loc + IVec2::new(0, 1),
-                            dir: Direction::South,
-                            consecutive_blocks: 0,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                    if s.consecutive_blocks < 9 {
-                        let next = State {
-                            loc: s.loc - IVec2::new(1, 0),
-                            dir: Direction::West,
-                            consecutive_blocks: s.consecutive_blocks + 1,
-                        };
-                        if let Some(heat_loss) = grid.blocks.get(&next.loc) {
-                            successors.push((next, *heat_loss));
-                        }
-                    }
-                }
-            }
-            successors
-        },
-        |s| s.loc == grid.bounds - IVec2::new(1, 1) && s.consecutive_blocks >= 3,
-    )
-    .unwrap();
-    total_heat_loss
+    min_heat_loss(text, Some(PART2_MIN_BEFORE_TURN), PART2_MAX_CONSECUTIVE)
+}
+
+pub fn min_heat_loss(text: String, min_before_turn: Option<i32>, max_consecutive: i32) -> u32 {
+    let crucible = Crucible {
+        grid: parse_grid(text),
+        min_before_turn,
+        max_consecutive,
+    };
+    astar(&crucible).unwrap().cost as u32
 }
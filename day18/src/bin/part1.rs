@@ -1,5 +1,5 @@
-use std::fs;
 use day18::part1;
+use std::fs;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
@@ -9,10 +9,17 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day18::part1_shoelace;
 
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
         assert_eq!(part1(text), 62)
     }
+
+    #[test]
+    fn part1_shoelace_agrees() {
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        assert_eq!(part1_shoelace(text), 62)
+    }
 }
@@ -5,13 +5,13 @@ use std::{
 
 use glam::I64Vec2;
 use nom::{
-    character::complete::{alphanumeric1, char, digit1, line_ending, one_of, space1},
-    combinator::map,
-    error::Error,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, line_ending, one_of, space1},
+    combinator::{map, verify},
     multi::separated_list1,
     sequence::{delimited, preceded, tuple},
-    IResult,
 };
+use parsing::{number, parse_all, IResult};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct Hole {
@@ -42,83 +42,103 @@ impl Display for Grid {
     }
 }
 
-fn get_grid(text: &str) -> IResult<&str, Grid> {
+/// A line of the dig plan, such as `R 6 (#70c710)`
+struct Instruction<'a> {
+    dir: char,
+    len: i64,
+    /// Six hex digits, the last of them 0 to 3
+    color: &'a str,
+}
+
+fn instruction(text: &str) -> IResult<'_, Instruction<'_>> {
     map(
-        separated_list1(
-            line_ending::<&str, Error<_>>,
-            tuple((
-                one_of("UDLR"),
+        tuple((
+            one_of("UDLR"),
+            preceded(space1, number),
+            preceded(
                 space1,
-                digit1,
-                space1,
-                delimited(char('('), preceded(char('#'), alphanumeric1), char(')')),
-            )),
-        ),
-        |lines| {
-            let mut blocks = HashMap::new();
-            let mut cur = I64Vec2::new(0, 0);
-            for (dir, _, len, _, color) in lines {
-                for _ in 0..len.parse().unwrap() {
-                    match dir {
-                        'U' => {
-                            cur -= I64Vec2::new(0, 1);
-                            blocks.insert(
-                                cur,
-                                Hole {
-                                    color: color.to_string(),
-                                },
-                            );
-                        }
-                        'D' => {
-                            cur += I64Vec2::new(0, 1);
-                            blocks.insert(
-                                cur,
-                                Hole {
-                                    color: color.to_string(),
-                                },
-                            );
-                        }
-                        'L' => {
-                            cur -= I64Vec2::new(1, 0);
-                            blocks.insert(
-                                cur,
-                                Hole {
-                                    color: color.to_string(),
-                                },
-                            );
-                        }
-                        'R' => {
-                            cur += I64Vec2::new(1, 0);
-                            blocks.insert(
-                                cur,
-                                Hole {
-                                    color: color.to_string(),
-                                },
-                            );
-                        }
-                        _ => panic!("Invalid direction"),
-                    }
+                delimited(
+                    tag("(#"),
+                    verify(
+                        take_while_m_n(6, 6, |c: char| c.is_ascii_hexdigit()),
+                        |color: &str| matches!(color.as_bytes()[5], b'0'..=b'3'),
+                    ),
+                    char(')'),
+                ),
+            ),
+        )),
+        |(dir, len, color)| Instruction { dir, len, color },
+    )(text)
+}
+
+fn instructions(text: &str) -> IResult<'_, Vec<Instruction<'_>>> {
+    separated_list1(line_ending, instruction)(text)
+}
+
+fn get_grid(text: &str) -> Grid {
+    let lines = parse_all(text, instructions).expect("Failed to parse.");
+    let mut blocks = HashMap::new();
+    let mut cur = I64Vec2::new(0, 0);
+    for Instruction { dir, len, color } in lines {
+        for _ in 0..len {
+            match dir {
+                'U' => {
+                    cur -= I64Vec2::new(0, 1);
+                    blocks.insert(
+                        cur,
+                        Hole {
+                            color: color.to_string(),
+                        },
+                    );
                 }
+                'D' => {
+                    cur += I64Vec2::new(0, 1);
+                    blocks.insert(
+                        cur,
+                        Hole {
+                            color: color.to_string(),
+                        },
+                    );
+                }
+                'L' => {
+                    cur -= I64Vec2::new(1, 0);
+                    blocks.insert(
+                        cur,
+                        Hole {
+                            color: color.to_string(),
+                        },
+                    );
+                }
+                'R' => {
+                    cur += I64Vec2::new(1, 0);
+                    blocks.insert(
+                        cur,
+                        Hole {
+                            color: color.to_string(),
+                        },
+                    );
+                }
+                _ => panic!("Invalid direction"),
             }
-            let bounds = blocks.iter().fold(
-                (I64Vec2::new(0, 0), I64Vec2::new(0, 0)),
-                |(acc_min, acc_max), (loc, _)| {
-                    let x = acc_min.x.min(loc.x);
-                    let y = acc_min.y.min(loc.y);
-                    let min = I64Vec2::new(x, y);
-                    let x = acc_max.x.max(loc.x);
-                    let y = acc_max.y.max(loc.y);
-                    let max = I64Vec2::new(x, y);
-                    (min, max)
-                },
-            );
-            Grid {
-                min: bounds.0,
-                max: bounds.1 + I64Vec2::new(1, 1),
-                blocks,
-            }
+        }
+    }
+    let bounds = blocks.iter().fold(
+        (I64Vec2::new(0, 0), I64Vec2::new(0, 0)),
+        |(acc_min, acc_max), (loc, _)| {
+            let x = acc_min.x.min(loc.x);
+            let y = acc_min.y.min(loc.y);
+            let min = I64Vec2::new(x, y);
+            let x = acc_max.x.max(loc.x);
+            let y = acc_max.y.max(loc.y);
+            let max = I64Vec2::new(x, y);
+            (min, max)
         },
-    )(text)
+    );
+    Grid {
+        min: bounds.0,
+        max: bounds.1 + I64Vec2::new(1, 1),
+        blocks,
+    }
 }
 
 fn count_interior(grid: &Grid) -> usize {
@@ -181,7 +201,7 @@ fn count_interior(grid: &Grid) -> usize {
 }
 
 pub fn part1(text: String) -> usize {
-    let (_, grid) = get_grid(text.as_str()).unwrap();
+    let grid = get_grid(text.as_str());
     count_interior(&grid)
 }
 
@@ -192,37 +212,44 @@ enum Direction {
     West,
 }
 
-fn get_grid_by_color(text: &str) -> IResult<&str, Vec<(Direction, i64)>> {
-    map(
-        separated_list1(
-            line_ending::<&str, Error<_>>,
-            tuple((
-                one_of("UDLR"),
-                space1,
-                digit1,
-                space1,
-                delimited(char('('), preceded(char('#'), alphanumeric1), char(')')),
-            )),
-        ),
-        |lines| {
-            let mut segments = Vec::new();
-            for (_, _, _, _, color) in lines {
-                let len = i64::from_str_radix(&color[..5], 16).unwrap();
-                let dir = i64::from_str_radix(&color[5..], 16).unwrap();
-                segments.push((
-                    match dir {
-                        3 => Direction::North,
-                        1 => Direction::South,
-                        0 => Direction::East,
-                        2 => Direction::West,
-                        _ => panic!("Invalid direction"),
-                    },
-                    len,
-                ));
-            }
-            segments
-        },
-    )(text)
+/// Which part of a dig plan line gives the direction and length
+#[derive(Clone, Copy)]
+enum Source {
+    /// The direction letter and the length after it
+    Plan,
+    /// The hex colour, whose first five digits are the length and last the
+    /// direction
+    Color,
+}
+
+fn segments(text: &str, source: Source) -> Vec<(Direction, i64)> {
+    parse_all(text, instructions)
+        .expect("Failed to parse.")
+        .into_iter()
+        .map(|Instruction { dir, len, color }| match source {
+            Source::Plan => (
+                match dir {
+                    'U' => Direction::North,
+                    'D' => Direction::South,
+                    'R' => Direction::East,
+                    'L' => Direction::West,
+                    _ => unreachable!("the parser only accepts UDLR"),
+                },
+                len,
+            ),
+            Source::Color => (
+                match &color[5..] {
+                    "3" => Direction::North,
+                    "1" => Direction::South,
+                    "0" => Direction::East,
+                    "2" => Direction::West,
+                    _ => unreachable!("the parser only accepts 0 to 3"),
+                },
+                // Five hex digits always fit
+                i64::from_str_radix(&color[..5], 16).unwrap(),
+            ),
+        })
+        .collect()
 }
 
 /// Calculate the interior of a polygon using the Triangle Form of the Shoelace Formula
@@ -240,8 +267,6 @@ fn shoelace(segments: &[(Direction, i64)]) -> i64 {
                     Direction::East => I64Vec2::new(*len, 0),
                     Direction::West => I64Vec2::new(-*len, 0),
                 };
-            dbg!(v1);
-            dbg!(v2);
             (v2, area + v1.x * v2.y - v2.x * v1.y + len)
         })
         .1
@@ -249,7 +274,12 @@ fn shoelace(segments: &[(Direction, i64)]) -> i64 {
         + 1
 }
 
+/// Part 1 again, but with the shoelace formula from part 2 instead of filling the grid
+pub fn part1_shoelace(text: String) -> i64 {
+    let segments = segments(&text, Source::Plan);
+    shoelace(&segments)
+}
+
 pub fn part2(text: String) -> i64 {
-    let (_, grid) = get_grid_by_color(text.as_str()).unwrap();
-    shoelace(&grid)
+    shoelace(&segments(&text, Source::Color))
 }
@@ -1,5 +1,5 @@
-use std::fs;
 use day19::part1;
+use std::fs;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
@@ -15,4 +15,22 @@ mod tests {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
         assert_eq!(part1(text), 19114)
     }
+
+    #[test]
+    fn trace_part() {
+        use day19::{parse_input, parse_part, trace};
+
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let (workflows, _) = parse_input(text.as_str()).unwrap();
+        let part = parse_part("{x=787,m=2655,a=1222,s=2876}").unwrap();
+        assert_eq!(
+            trace(&workflows, &part),
+            (vec!["in", "qqz", "qs", "lnx"], true)
+        );
+        let part = parse_part("{x=1679,m=44,a=2067,s=496}").unwrap();
+        assert_eq!(
+            trace(&workflows, &part),
+            (vec!["in", "px", "rfg", "gd"], false)
+        );
+    }
 }
@@ -3,25 +3,24 @@ use std::collections::HashMap;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, char, digit1, line_ending, multispace1, one_of},
+    character::complete::{alpha1, char, line_ending, multispace1, one_of},
     combinator::map,
-    error::Error,
-    multi::{fold_many1, separated_list1},
-    sequence::{delimited, preceded, terminated, tuple},
-    IResult,
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, tuple},
 };
+use parsing::{number, parse_all, IResult, Located};
 
 #[derive(Debug)]
-enum Destination<'a> {
+pub enum Destination<'a> {
     A,
     R,
     Workflow(&'a str),
 }
 
 #[derive(Debug)]
-struct Rule<'a> {
-    condition: Option<(char, char, u32)>,
-    dest: Destination<'a>,
+pub struct Rule<'a> {
+    pub condition: Option<(char, char, u32)>,
+    pub dest: Destination<'a>,
 }
 
 impl Rule<'_> {
@@ -227,17 +226,17 @@ impl Rule<'_> {
 }
 
 #[derive(Debug)]
-struct Workflow<'a> {
-    name: &'a str,
-    rules: Vec<Rule<'a>>,
+pub struct Workflow<'a> {
+    pub name: &'a str,
+    pub rules: Vec<Rule<'a>>,
 }
 
 #[derive(Debug)]
-struct Part {
-    x: u32,
-    m: u32,
-    a: u32,
-    s: u32,
+pub struct Part {
+    pub x: u32,
+    pub m: u32,
+    pub a: u32,
+    pub s: u32,
 }
 
 impl Part {
@@ -246,106 +245,109 @@ impl Part {
     }
 }
 
-fn parse_rule(text: &str) -> IResult<&str, Rule> {
+fn destination(name: &str) -> Destination<'_> {
+    match name {
+        "A" => Destination::A,
+        "R" => Destination::R,
+        workflow => Destination::Workflow(workflow),
+    }
+}
+
+/// `x>10:one` or a bare destination
+fn rule(text: &str) -> IResult<'_, Rule<'_>> {
     alt((
         map(
             tuple((
                 one_of("xmas"),
                 one_of("<>"),
-                digit1,
+                number,
                 preceded(char(':'), alpha1),
             )),
-            |(var, op, num, dest)| {
-                let dest = match dest {
-                    "A" => Destination::A,
-                    "R" => Destination::R,
-                    workflow => Destination::Workflow(workflow),
-                };
-                Rule {
-                    condition: Some((var, op, num.parse().unwrap())),
-                    dest,
-                }
+            |(var, op, num, dest)| Rule {
+                condition: Some((var, op, num)),
+                dest: destination(dest),
             },
         ),
-        map(alpha1, |dest| {
-            let dest = match dest {
-                "A" => Destination::A,
-                "R" => Destination::R,
-                workflow => Destination::Workflow(workflow),
-            };
-            Rule {
-                condition: None,
-                dest,
-            }
+        map(alpha1, |dest| Rule {
+            condition: None,
+            dest: destination(dest),
         }),
     ))(text)
 }
 
-fn parse_workflow(text: &str) -> IResult<&str, Workflow> {
+fn workflow(text: &str) -> IResult<'_, Workflow<'_>> {
     map(
         tuple((
             alpha1,
-            delimited(char('{'), separated_list1(char(','), parse_rule), char('}')),
+            delimited(char('{'), separated_list1(char(','), rule), char('}')),
         )),
         |(name, rules)| Workflow { name, rules },
     )(text)
 }
 
-fn parse_part(text: &str) -> IResult<&str, Part> {
+fn part(text: &str) -> IResult<'_, Part> {
     map(
         delimited(
-            char::<&str, Error<&str>>('{'),
+            char('{'),
             tuple((
-                delimited(tag("x="), digit1, char(',')),
-                delimited(tag("m="), digit1, char(',')),
-                delimited(tag("a="), digit1, char(',')),
-                preceded(tag("s="), digit1),
+                delimited(tag("x="), number, char(',')),
+                delimited(tag("m="), number, char(',')),
+                delimited(tag("a="), number, char(',')),
+                preceded(tag("s="), number),
             )),
             char('}'),
         ),
-        |(x, m, a, s)| Part {
-            x: x.parse::<u32>().unwrap(),
-            m: m.parse::<u32>().unwrap(),
-            a: a.parse::<u32>().unwrap(),
-            s: s.parse::<u32>().unwrap(),
-        },
+        |(x, m, a, s)| Part { x, m, a, s },
     )(text)
 }
 
-fn parse_input(text: &str) -> IResult<&str, (HashMap<&str, Workflow>, Vec<Part>)> {
-    let (text, workflows) = fold_many1(
-        terminated(parse_workflow, line_ending),
-        HashMap::new,
-        |mut acc, item| {
-            acc.insert(item.name, item);
-            acc
-        },
-    )(text)?;
-    let (text, parts) = preceded(multispace1, separated_list1(multispace1, parse_part))(text)?;
-    Ok((text, (workflows, parts)))
+/// A part on its own, such as `{x=787,m=2655,a=1222,s=2876}`
+pub fn parse_part(text: &str) -> Result<Part, Located> {
+    parse_all(text.trim(), part)
+}
+
+/// The workflows keyed by name, then the parts after a blank line
+pub fn parse_input(text: &str) -> Result<(HashMap<&str, Workflow<'_>>, Vec<Part>), Located> {
+    let (workflows, parts) = parse_all(
+        text,
+        separated_pair(
+            separated_list1(line_ending, workflow),
+            multispace1,
+            separated_list1(multispace1, part),
+        ),
+    )?;
+    let workflows = workflows
+        .into_iter()
+        .map(|workflow| (workflow.name, workflow))
+        .collect();
+    Ok((workflows, parts))
+}
+
+/// The workflows `part` goes through starting from `in`, and whether it ends up
+/// accepted
+pub fn trace<'a>(workflows: &HashMap<&'a str, Workflow<'a>>, part: &Part) -> (Vec<&'a str>, bool) {
+    let mut path = vec!["in"];
+    loop {
+        let workflow = workflows.get(path.last().unwrap()).unwrap();
+        let rule = workflow
+            .rules
+            .iter()
+            .find(|rule| rule.applies(part))
+            .expect("Failed to find a rule that applies");
+        match rule.dest {
+            Destination::A => return (path, true),
+            Destination::R => return (path, false),
+            Destination::Workflow(w) => path.push(w),
+        }
+    }
 }
 
 pub fn part1(text: String) -> u32 {
-    let (_, (workflows, parts)) = parse_input(text.as_str()).unwrap();
+    let (workflows, parts) = parse_input(text.as_str()).expect("Failed to parse.");
     parts
         .into_iter()
-        .filter_map(|part| {
-            let mut workflow = "in";
-            loop {
-                for rule in workflows.get(workflow).unwrap().rules.iter() {
-                    if rule.applies(&part) {
-                        match rule.dest {
-                            Destination::A => return Some(part.total_ratings()),
-                            Destination::R => return None,
-                            Destination::Workflow(w) => {
-                                workflow = w;
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        })
+        .filter(|part| trace(&workflows, part).1)
+        .map(|part| part.total_ratings())
         .sum()
 }
 
@@ -379,7 +381,7 @@ impl PartRange {
 }
 
 pub fn part2(text: String) -> u64 {
-    let (_, (workflows, _)) = parse_input(text.as_str()).unwrap();
+    let (workflows, _) = parse_input(text.as_str()).expect("Failed to parse.");
     let mut combinations = 0u64;
     let mut part_ranges = Vec::new();
     part_ranges.push((
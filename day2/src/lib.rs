@@ -1,69 +1,584 @@
-use nom::IResult;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
 use nom::character::complete::*;
+use nom::combinator::{map, not, value};
+use nom::error::context;
 use nom::multi::*;
-use nom::combinator::*;
 use nom::sequence::*;
+use parsing::{parse_all, solve_lines, IResult, Located};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
-struct Bag {
-    red: u32,
-    green: u32,
-    blue: u32,
+/// Cubes of any number of colours, a colour that isn't in the bag has none
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bag {
+    cubes: BTreeMap<String, u32>,
 }
 
+/// Bags are ordered colour by colour, so two bags where each has more of
+/// some colour than the other can't be compared
 impl PartialOrd for Bag {
     fn partial_cmp(&self, other: &Bag) -> Option<Ordering> {
-        if self.eq(other) {
-            Some(Ordering::Equal)
-        } else if self.red <= other.red && self.green <= other.green && self.blue <= other.blue {
-            Some(Ordering::Less)
-        } else {
-            Some(Ordering::Greater)
+        match (other.dominates(self), self.dominates(other)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
         }
     }
 }
 
 impl Bag {
-    fn power(&self) -> u32 {
-        self.red * self.green * self.blue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, color: &str, n: u32) {
+        if n == 0 {
+            self.cubes.remove(color);
+        } else {
+            self.cubes.insert(color.to_string(), n);
+        }
+    }
+
+    /// The colours in the bag with how many cubes there are of each
+    pub fn colors(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.cubes.iter().map(|(color, n)| (color.as_str(), *n))
+    }
+
+    /// Whether the bag has at least as many cubes of every colour as `other`
+    pub fn dominates(&self, other: &Bag) -> bool {
+        other.colors().all(|(color, n)| self.get(color) >= n)
+    }
+
+    /// The smallest bag that dominates both bags
+    pub fn join(&self, other: &Bag) -> Bag {
+        let mut join = self.clone();
+        for (color, n) in other.colors() {
+            join.set(color, n.max(self.get(color)));
+        }
+        join
+    }
+
+    /// The largest bag that both bags dominate
+    pub fn meet(&self, other: &Bag) -> Bag {
+        self.colors()
+            .map(|(color, n)| (color, n.min(other.get(color))))
+            .collect()
+    }
+
+    /// The product of the counts of every colour in the bag
+    pub fn power(&self) -> u64 {
+        self.colors().map(|(_, n)| n as u64).product()
     }
 }
 
-#[derive(Debug)]
-struct Game {
-    id: u32,
-    rounds: Vec<Bag>,
+impl<S: AsRef<str>> FromIterator<(S, u32)> for Bag {
+    fn from_iter<I: IntoIterator<Item = (S, u32)>>(iter: I) -> Self {
+        let mut bag = Bag::new();
+        for (color, n) in iter {
+            bag.set(color.as_ref(), n);
+        }
+        bag
+    }
 }
 
-fn bag(text: &str) -> IResult<&str, Bag> {
-    let mut bag = Bag {
-        red: 0,
-        green: 0,
-        blue: 0,
-    };
-    let (text, _) = separated_list1(
-        pair(char(','), space1), 
-        map(
-            separated_pair(u32, space1, alpha1),
-            |(n, color): (u32, &str)| {
-                match color {
-                    "red" => bag.red = n,
-                    "green" => bag.green = n,
-                    "blue" => bag.blue = n,
-                    _ => panic!(),
+/// A bag written like a round, `12 red, 13 green, 14 blue`
+impl FromStr for Bag {
+    type Err = Located;
+
+    fn from_str(text: &str) -> Result<Self, Located> {
+        parse_all(text.trim(), bag)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Bag>,
+}
+
+impl Game {
+    /// The fewest cubes of each colour that the game could have been played with
+    pub fn minimal_bag(&self) -> Bag {
+        self.rounds.iter().fold(Bag::new(), |bag, round| bag.join(round))
+    }
+
+    /// Whether the game could have been played with `bag`
+    pub fn feasible_under(&self, bag: &Bag) -> bool {
+        self.rounds.iter().all(|round| round <= bag)
+    }
+}
+
+/// The ids of the games that could have been played with `bag`
+pub fn feasible_games(games: &[Game], bag: &Bag) -> Vec<u32> {
+    games
+        .iter()
+        .filter(|game| game.feasible_under(bag))
+        .map(|game| game.id)
+        .collect()
+}
+
+/// The smallest bag that every one of the games could have been played with
+pub fn smallest_bag<'a>(games: impl IntoIterator<Item = &'a Game>) -> Bag {
+    games
+        .into_iter()
+        .fold(Bag::new(), |bag, game| bag.join(&game.minimal_bag()))
+}
+
+pub fn parse_games(text: &str) -> Result<Vec<Game>, Located> {
+    solve_lines(text, |line| parse_all(line, parse))
+}
+
+/// What's believed about how many cubes of a colour there are before looking
+/// at any draws
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prior {
+    /// Any count up to the estimator's maximum is as likely as any other
+    Uniform,
+    /// Counts are Poisson distributed around `mean`
+    Poisson { mean: f64 },
+}
+
+/// `uniform` or `poisson:MEAN`
+impl FromStr for Prior {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        match text.split_once(':') {
+            None if text == "uniform" => Ok(Prior::Uniform),
+            Some(("poisson", mean)) => match mean.parse() {
+                Ok(mean) if mean > 0.0 => Ok(Prior::Poisson { mean }),
+                _ => Err(format!("{} is not a positive mean", mean)),
+            },
+            _ => Err(format!("{} is not uniform or poisson:MEAN", text)),
+        }
+    }
+}
+
+impl Prior {
+    fn ln_prob(&self, n: u32, ln_fact: &[f64]) -> f64 {
+        match self {
+            Prior::Uniform => 0.0,
+            Prior::Poisson { mean } => n as f64 * mean.ln() - mean - ln_fact[n as usize],
+        }
+    }
+}
+
+/// The most likely contents of the hidden bag, and for each colour the range
+/// of counts that holds the estimator's `confidence` of the posterior
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub most_likely: Bag,
+    pub intervals: BTreeMap<String, (u32, u32)>,
+}
+
+/// Infers the bag from the rounds drawn from it
+///
+/// Each round is a handful drawn without replacement and put back afterwards,
+/// so its probability is multivariate hypergeometric. The estimate is the
+/// posterior mode under `prior`, which with the uniform prior is the maximum
+/// likelihood. Counts above `max_count` aren't considered, since with a
+/// uniform prior the likelihood can keep growing with the size of the bag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimator {
+    pub prior: Prior,
+    pub max_count: u32,
+    pub confidence: f64,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Estimator {
+            prior: Prior::Uniform,
+            max_count: 100,
+            confidence: 0.95,
+        }
+    }
+}
+
+fn ln_choose(n: u32, k: u32, ln_fact: &[f64]) -> f64 {
+    if k > n {
+        f64::NEG_INFINITY
+    } else {
+        ln_fact[n as usize] - ln_fact[k as usize] - ln_fact[(n - k) as usize]
+    }
+}
+
+fn ln_sum_exp(values: impl Iterator<Item = f64> + Clone) -> f64 {
+    let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.map(|value| (value - max).exp()).sum::<f64>().ln()
+}
+
+/// Combines two functions of a count into a function of their total, keeping
+/// the best split with `max` or adding them all up with `ln_sum_exp`
+fn convolve(a: &[f64], b: &[f64], sum: bool) -> (Vec<f64>, Vec<usize>) {
+    let mut out = vec![f64::NEG_INFINITY; a.len() + b.len() - 1];
+    let mut split = vec![0; out.len()];
+    for (total, (out, split)) in out.iter_mut().zip(&mut split).enumerate() {
+        let lo = total.saturating_sub(a.len() - 1);
+        let hi = total.min(b.len() - 1);
+        let terms = (lo..=hi).map(|n| a[total - n] + b[n]);
+        if sum {
+            *out = ln_sum_exp(terms);
+        } else if let Some((n, best)) = (lo..=hi).zip(terms).max_by(|(_, x), (_, y)| x.total_cmp(y))
+        {
+            (*out, *split) = (best, n);
+        }
+    }
+    (out, split)
+}
+
+impl Estimator {
+    /// `None` if some round drew more of a colour than `max_count`, or if
+    /// `confidence` isn't between 0 and 1
+    pub fn estimate<'a>(&self, rounds: impl IntoIterator<Item = &'a Bag>) -> Option<Estimate> {
+        if !(0.0..=1.0).contains(&self.confidence) {
+            return None;
+        }
+        let rounds: Vec<&Bag> = rounds.into_iter().collect();
+        let seen = rounds.iter().fold(Bag::new(), |bag, round| bag.join(round));
+        let colors: Vec<&str> = seen.colors().map(|(color, _)| color).collect();
+        if colors.is_empty() || seen.colors().any(|(_, n)| n > self.max_count) {
+            return None;
+        }
+        let max = self.max_count as usize;
+        let ln_fact: Vec<f64> = (0..=max * colors.len())
+            .scan(0.0, |ln_fact, n| {
+                if n > 0 {
+                    *ln_fact += (n as f64).ln();
+                }
+                Some(*ln_fact)
+            })
+            .collect();
+        // Everything that depends on one colour's count, prior included
+        let per_color: Vec<Vec<f64>> = colors
+            .iter()
+            .map(|color| {
+                (0..=self.max_count)
+                    .map(|n| {
+                        self.prior.ln_prob(n, &ln_fact)
+                            + rounds
+                                .iter()
+                                .map(|round| ln_choose(n, round.get(color), &ln_fact))
+                                .sum::<f64>()
+                    })
+                    .collect()
+            })
+            .collect();
+        // And what depends on the total, the ways of choosing each round's
+        // handful, where a total smaller than a handful is impossible
+        let handfuls: Vec<u32> = rounds
+            .iter()
+            .map(|round| round.colors().map(|(_, n)| n).sum())
+            .collect();
+        let by_total: Vec<f64> = (0..=self.max_count * colors.len() as u32)
+            .map(
+                |total| match handfuls.iter().any(|&handful| handful > total) {
+                    true => f64::NEG_INFINITY,
+                    false => -handfuls
+                        .iter()
+                        .map(|&handful| ln_choose(total, handful, &ln_fact))
+                        .sum::<f64>(),
+                },
+            )
+            .collect();
+
+        // The mode, keeping each colour's count at every total to trace it back
+        let mut best = per_color[0].clone();
+        let mut splits = Vec::new();
+        for color in &per_color[1..] {
+            let (next, split) = convolve(&best, color, false);
+            best = next;
+            splits.push(split);
+        }
+        let mut total = (0..best.len())
+            .max_by(|&x, &y| (best[x] + by_total[x]).total_cmp(&(best[y] + by_total[y])))
+            .unwrap();
+        let mut counts = vec![0; colors.len()];
+        for (idx, split) in splits.iter().enumerate().rev() {
+            counts[idx + 1] = split[total];
+            total -= split[total];
+        }
+        counts[0] = total;
+
+        // Each colour's marginal, adding up every way the others could be filled
+        let tail = (1.0 - self.confidence) / 2.0;
+        let intervals = colors
+            .iter()
+            .enumerate()
+            .map(|(idx, color)| {
+                let others = per_color
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != idx)
+                    .map(|(_, others)| others.clone())
+                    .reduce(|a, b| convolve(&a, &b, true).0)
+                    .unwrap_or_else(|| vec![0.0]);
+                let ln_marginal: Vec<f64> = (0..=max)
+                    .map(|n| {
+                        per_color[idx][n]
+                            + ln_sum_exp(
+                                others
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(rest, ln_p)| ln_p + by_total[n + rest]),
+                            )
+                    })
+                    .collect();
+                let ln_norm = ln_sum_exp(ln_marginal.iter().copied());
+                let mut cdf = 0.0;
+                let mut low = None;
+                let mut high = self.max_count;
+                for (n, ln_p) in ln_marginal.iter().enumerate() {
+                    cdf += (ln_p - ln_norm).exp();
+                    if cdf > tail && low.is_none() {
+                        low = Some(n as u32);
+                    }
+                    if cdf >= 1.0 - tail {
+                        high = n as u32;
+                        break;
+                    }
+                }
+                (color.to_string(), (low.unwrap(), high))
+            })
+            .collect();
+
+        Some(Estimate {
+            most_likely: colors
+                .iter()
+                .zip(counts)
+                .map(|(color, n)| (*color, n as u32))
+                .collect(),
+            intervals,
+        })
+    }
+
+    pub fn estimate_game(&self, game: &Game) -> Option<Estimate> {
+        self.estimate(&game.rounds)
+    }
+
+    /// The bag behind every game, if they were all drawn from the same one
+    pub fn estimate_all(&self, games: &[Game]) -> Option<Estimate> {
+        self.estimate(games.iter().flat_map(|game| &game.rounds))
+    }
+}
+
+/// Something about a game that a query can compare
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    Number(u64),
+    Id,
+    /// How many rounds the game has
+    Rounds,
+    /// The power of the game's minimal bag
+    Power,
+    /// The most cubes of the colour drawn in any round
+    Max(String),
+    /// The fewest cubes of the colour drawn in any round, rounds without the
+    /// colour count as none
+    Min(String),
+}
+
+impl Field {
+    pub fn eval(&self, game: &Game) -> u64 {
+        let counts = |color: &str| -> Vec<u32> {
+            game.rounds.iter().map(|round| round.get(color)).collect()
+        };
+        match self {
+            Field::Number(n) => *n,
+            Field::Id => game.id as u64,
+            Field::Rounds => game.rounds.len() as u64,
+            Field::Power => game.minimal_bag().power(),
+            Field::Max(color) => counts(color).into_iter().max().unwrap_or(0) as u64,
+            Field::Min(color) => counts(color).into_iter().min().unwrap_or(0) as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A filter over games, such as `max(red) > 10 and not rounds < 3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Compare(Field, Comparison, Field),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+}
+
+impl Query {
+    pub fn matches(&self, game: &Game) -> bool {
+        match self {
+            Query::Compare(left, comparison, right) => {
+                let (left, right) = (left.eval(game), right.eval(game));
+                match comparison {
+                    Comparison::Eq => left == right,
+                    Comparison::Ne => left != right,
+                    Comparison::Lt => left < right,
+                    Comparison::Le => left <= right,
+                    Comparison::Gt => left > right,
+                    Comparison::Ge => left >= right,
                 }
             }
-           )
-        )(text)?;
-    Ok((text, bag))
+            Query::And(a, b) => a.matches(game) && b.matches(game),
+            Query::Or(a, b) => a.matches(game) || b.matches(game),
+            Query::Not(query) => !query.matches(game),
+        }
+    }
+
+    /// The games the query matches, with some totals over them
+    pub fn run(&self, games: &[Game]) -> QueryResult {
+        let matching: Vec<&Game> = games.iter().filter(|game| self.matches(game)).collect();
+        QueryResult {
+            ids: matching.iter().map(|game| game.id).collect(),
+            id_sum: matching.iter().map(|game| game.id as u64).sum(),
+            power_sum: matching.iter().map(|game| game.minimal_bag().power()).sum(),
+            smallest_bag: smallest_bag(matching),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = Located;
+
+    fn from_str(text: &str) -> Result<Self, Located> {
+        parse_all(text, preceded(space0, query))
+    }
 }
 
-fn rounds(text: &str) -> IResult<&str, Vec<Bag>> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryResult {
+    pub ids: Vec<u32>,
+    pub id_sum: u64,
+    pub power_sum: u64,
+    /// The smallest bag all of the matching games could have been played with
+    pub smallest_bag: Bag,
+}
+
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids: Vec<String> = self.ids.iter().map(|id| id.to_string()).collect();
+        let bag: Vec<String> = self
+            .smallest_bag
+            .colors()
+            .map(|(color, n)| format!("{} {}", n, color))
+            .collect();
+        writeln!(f, "games: {}", ids.join(" "))?;
+        writeln!(f, "count: {}", self.ids.len())?;
+        writeln!(f, "id sum: {}", self.id_sum)?;
+        writeln!(f, "power sum: {}", self.power_sum)?;
+        write!(f, "smallest bag: {}", bag.join(", "))
+    }
+}
+
+/// `parser` and any spaces after it
+fn token<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+    terminated(parser, space0)
+}
+
+/// A word that isn't just the start of a longer one, so `or` doesn't match `orange`
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    token(terminated(tag(word), not(alphanumeric1)))
+}
+
+fn field(text: &str) -> IResult<'_, Field> {
+    let color = |text| delimited(token(char('(')), token(alpha1), token(char(')')))(text);
+    context(
+        "field",
+        alt((
+            map(token(u64), Field::Number),
+            value(Field::Id, keyword("id")),
+            value(Field::Rounds, keyword("rounds")),
+            value(Field::Power, keyword("power")),
+            map(preceded(keyword("max"), color), |color: &str| {
+                Field::Max(color.to_string())
+            }),
+            map(preceded(keyword("min"), color), |color: &str| {
+                Field::Min(color.to_string())
+            }),
+        )),
+    )(text)
+}
+
+fn comparison(text: &str) -> IResult<'_, Comparison> {
+    context(
+        "comparison",
+        token(alt((
+            value(Comparison::Eq, tag("==")),
+            value(Comparison::Ne, tag("!=")),
+            value(Comparison::Le, tag("<=")),
+            value(Comparison::Ge, tag(">=")),
+            value(Comparison::Lt, tag("<")),
+            value(Comparison::Gt, tag(">")),
+            value(Comparison::Eq, tag("=")),
+        ))),
+    )(text)
+}
+
+fn term(text: &str) -> IResult<'_, Query> {
+    alt((
+        map(preceded(keyword("not"), term), |query| {
+            Query::Not(Box::new(query))
+        }),
+        delimited(token(char('(')), query, context("')'", token(char(')')))),
+        map(
+            tuple((field, comparison, field)),
+            |(left, comparison, right)| Query::Compare(left, comparison, right),
+        ),
+    ))(text)
+}
+
+fn conjunction(text: &str) -> IResult<'_, Query> {
+    let (text, first) = term(text)?;
+    fold_many0(
+        preceded(alt((keyword("and"), token(tag("&&")))), term),
+        move || first.clone(),
+        |a, b| Query::And(Box::new(a), Box::new(b)),
+    )(text)
+}
+
+fn query(text: &str) -> IResult<'_, Query> {
+    let (text, first) = conjunction(text)?;
+    fold_many0(
+        preceded(alt((keyword("or"), token(tag("||")))), conjunction),
+        move || first.clone(),
+        |a, b| Query::Or(Box::new(a), Box::new(b)),
+    )(text)
+}
+
+fn bag(text: &str) -> IResult<'_, Bag> {
+    let (text, cubes) = separated_list1(
+        pair(char(','), space1),
+        separated_pair(u32, space1, alpha1),
+    )(text)?;
+    Ok((text, cubes.into_iter().map(|(n, color)| (color, n)).collect()))
+}
+
+fn rounds(text: &str) -> IResult<'_, Vec<Bag>> {
     separated_list1(pair(char(';'), space1), bag)(text)
 }
 
-fn parse(text: &str) -> IResult<&str, Game> {
+fn parse(text: &str) -> IResult<'_, Game> {
     let (text, _) = pair(alpha1, space1)(text)?;
     let (text, id) = u32(text)?;
     let (text, _) = pair(char(':'), space1)(text)?;
@@ -77,45 +592,30 @@ fn parse(text: &str) -> IResult<&str, Game> {
        ))
 }
 
+/// How many cubes of each colour the elf has in the bag for part 1
+pub const PART1_BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
 pub fn part1(text: String) -> u32 {
-    let config = Bag {
-        red: 12,
-        green: 13,
-        blue: 14,
-    };
-    let mut sum = 0;
-    for line in text.lines() {
-        let (_, game) = parse(line).expect("Failed to parse.");
-        let possible = game.rounds.iter().all(|round: &Bag| {
-            round <= &config
-        });
-        if possible {
-            sum += game.id;
-        }
-    }
-    sum
+    possible_games(text, &PART1_BAG.into_iter().collect())
 }
 
-pub fn part2(text: String) -> u32 {
-    let mut sum = 0;
-    for line in text.lines() {
-        let (_, game) = parse(line).expect("Failed to parse.");
-        let mut req_bag = Bag {red: 0, green: 0, blue: 0};
-        for bag in game.rounds.iter() {
-            if bag <= &req_bag {
-                continue;
-            }
-            if bag.red > req_bag.red {
-                req_bag.red = bag.red;
-            }
-            if bag.green > req_bag.green {
-                req_bag.green = bag.green;
-            }
-            if bag.blue > req_bag.blue {
-                req_bag.blue = bag.blue;
-            }
-        }
-        sum += req_bag.power();
-    }
-    sum
+/// Sums the ids of the games that could have been played with the given bag
+pub fn possible_games(text: String, config: &Bag) -> u32 {
+    solve_lines(&text, |line| {
+        let game = parse_all(line, parse)?;
+        Ok(if game.feasible_under(config) { game.id } else { 0 })
+    })
+    .expect("Failed to parse.")
+    .iter()
+    .sum()
+}
+
+pub fn part2(text: String) -> u64 {
+    solve_lines(&text, |line| {
+        let game = parse_all(line, parse)?;
+        Ok(game.minimal_bag().power())
+    })
+    .expect("Failed to parse.")
+    .iter()
+    .sum()
 }
@@ -1,7 +1,57 @@
-use std::fs;
-use day2::{part1, part2};
+use std::{env, fs};
+use day2::{part1, part2, possible_games, Bag, Estimate, Estimator, Query};
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match &args[..] {
+        [] => {}
+        // `--bag "12 red, 13 green, 14 blue"` checks the games against another bag
+        [flag, value] if flag == "--bag" => {
+            let config: Bag = value.parse().expect("Failed to parse the bag");
+            let text = fs::read_to_string("data/input.txt").unwrap();
+            println!("{}", possible_games(text, &config));
+            return;
+        }
+        // `--estimate uniform|poisson:MEAN` infers the bags behind the games
+        [flag, value] if flag == "--estimate" => {
+            let estimator = Estimator {
+                prior: value.parse().expect("Failed to parse the prior"),
+                ..Estimator::default()
+            };
+            let text = fs::read_to_string("data/input.txt").unwrap();
+            let games = day2::parse_games(&text).expect("Failed to parse.");
+            let show = |estimate: Option<Estimate>| match estimate {
+                Some(estimate) => estimate
+                    .most_likely
+                    .colors()
+                    .map(|(color, n)| {
+                        let (low, high) = estimate.intervals[color];
+                        format!("{} {} ({}..={})", n, color, low, high)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => "nothing drawn".to_string(),
+            };
+            for game in &games {
+                println!("Game {}: {}", game.id, show(estimator.estimate_game(game)));
+            }
+            println!("All games: {}", show(estimator.estimate_all(&games)));
+            return;
+        }
+        // `--query "max(red) > 10 and rounds < 4"` picks out games
+        [flag, value] if flag == "--query" => {
+            let query: Query = value.parse().expect("Failed to parse the query");
+            let text = fs::read_to_string("data/input.txt").unwrap();
+            let games = day2::parse_games(&text).expect("Failed to parse.");
+            println!("{}", query.run(&games));
+            return;
+        }
+        [flag] if ["--bag", "--estimate", "--query"].contains(&flag.as_str()) => {
+            panic!("Failed to find a value for {}", flag)
+        }
+        [arg, ..] => panic!("Failed to understand argument {}", arg),
+    }
+
     let text = fs::read_to_string("data/input.txt").unwrap();
     println!("{}", part1(text));
 
@@ -24,4 +74,128 @@ mod tests {
         let text = fs::read_to_string("data/part2_example.txt").unwrap();
         assert_eq!(part2(text), 2286)
     }
+
+    #[test]
+    fn any_colors() {
+        let text = "Game 1: 2 red, 1 purple; 3 teal\nGame 2: 4 purple, 1 red\n".to_string();
+        let config: Bag = "3 red, 3 purple, 3 teal".parse().unwrap();
+        assert_eq!(possible_games(text.clone(), &config), 1);
+        assert_eq!(config.get("green"), 0);
+        assert_eq!(part2(text), 6 + 4);
+        assert!("3 red,".parse::<Bag>().is_err());
+    }
+
+    #[test]
+    fn bag_order() {
+        let bag = |text: &str| text.parse::<Bag>().unwrap();
+        assert!(bag("1 red, 2 blue") < bag("2 red, 2 blue"));
+        assert_eq!(bag("1 red").partial_cmp(&bag("1 blue")), None);
+        assert_eq!(bag("1 red, 0 blue"), bag("1 red"));
+        assert!(bag("3 red, 1 blue").dominates(&bag("2 red")));
+        assert_eq!(
+            bag("3 red, 1 blue").join(&bag("2 red, 4 green")),
+            bag("3 red, 1 blue, 4 green")
+        );
+        assert_eq!(bag("3 red, 1 blue").meet(&bag("2 red, 4 green")), bag("2 red"));
+
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let games = day2::parse_games(&text).unwrap();
+        assert_eq!(games[0].minimal_bag(), bag("4 red, 2 green, 6 blue"));
+        assert_eq!(
+            day2::feasible_games(&games, &bag("12 red, 13 green, 14 blue")),
+            vec![1, 2, 5]
+        );
+        assert_eq!(day2::smallest_bag(&games[..2]), bag("4 red, 3 green, 6 blue"));
+        assert_eq!(
+            day2::feasible_games(&games, &day2::smallest_bag(&games[..2])),
+            vec![1, 2]
+        );
+    }
+
+    /// The log posterior of a bag worked out directly, to check the estimator against
+    fn ln_posterior(bag: &Bag, rounds: &[Bag], mean: f64) -> f64 {
+        let ln_fact = |n: u32| (1..=n).map(|k| (k as f64).ln()).sum::<f64>();
+        let ln_choose = |n: u32, k: u32| match k > n {
+            true => f64::NEG_INFINITY,
+            false => ln_fact(n) - ln_fact(k) - ln_fact(n - k),
+        };
+        let total: u32 = bag.colors().map(|(_, n)| n).sum();
+        let prior: f64 = bag
+            .colors()
+            .map(|(_, n)| n as f64 * mean.ln() - mean - ln_fact(n))
+            .sum();
+        prior
+            + rounds
+                .iter()
+                .map(|round| {
+                    round
+                        .colors()
+                        .map(|(color, n)| ln_choose(bag.get(color), n))
+                        .sum::<f64>()
+                        - ln_choose(total, round.colors().map(|(_, n)| n).sum())
+                })
+                .sum::<f64>()
+    }
+
+    #[test]
+    fn estimates() {
+        let rounds: Vec<Bag> = ["3 red, 1 blue", "1 red, 2 blue", "4 red"]
+            .iter()
+            .map(|round| round.parse().unwrap())
+            .collect();
+        let estimator = Estimator {
+            prior: "poisson:5".parse().unwrap(),
+            max_count: 12,
+            confidence: 0.9,
+        };
+        let estimate = estimator.estimate(&rounds).unwrap();
+        let mut best = f64::NEG_INFINITY;
+        for red in 0..=12 {
+            for blue in 0..=12 {
+                let bag = Bag::from_iter([("red", red), ("blue", blue)]);
+                best = best.max(ln_posterior(&bag, &rounds, 5.0));
+            }
+        }
+        let found = ln_posterior(&estimate.most_likely, &rounds, 5.0);
+        assert!((found - best).abs() < 1e-9);
+        let (low, high) = estimate.intervals["red"];
+        assert!(low >= 4 && low <= estimate.most_likely.get("red") && high <= 12);
+
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let games = day2::parse_games(&text).unwrap();
+        let all = Estimator::default().estimate_all(&games).unwrap();
+        assert!(all.most_likely.dominates(&day2::smallest_bag(&games)));
+        assert!(Estimator::default().estimate(&[]).is_none());
+        for confidence in [-0.1, 1.5, f64::NAN] {
+            let estimator = Estimator {
+                confidence,
+                ..Estimator::default()
+            };
+            assert!(estimator.estimate(&rounds).is_none());
+        }
+        assert!("poisson:-1".parse::<day2::Prior>().is_err());
+    }
+
+    #[test]
+    fn queries() {
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let games = day2::parse_games(&text).unwrap();
+        let ids = |query: &str| query.parse::<Query>().unwrap().run(&games).ids;
+        assert_eq!(
+            ids("max(red) > 12 or max(green) > 13 or max(blue) > 14"),
+            vec![3, 4]
+        );
+        assert_eq!(ids("not (max(red) > 12 || max(blue) > 14)"), vec![1, 2, 5]);
+        assert_eq!(ids("rounds < 3 and id != 1"), vec![5]);
+        assert_eq!(ids("min(red) = 0 && power <= 48"), vec![1, 2]);
+        assert_eq!(ids("max(orange) >= 1"), Vec::<u32>::new());
+        assert_eq!(ids("max(red)>=id"), vec![1, 3, 4, 5]);
+
+        let result = "id > 0".parse::<Query>().unwrap().run(&games);
+        assert_eq!((result.id_sum, result.power_sum), (15, 2286));
+        assert_eq!(result.smallest_bag, day2::smallest_bag(&games));
+        assert!("max(red) >".parse::<Query>().is_err());
+        assert!("rounds < 3 andid = 1".parse::<Query>().is_err());
+        assert!("(id = 1".parse::<Query>().is_err());
+    }
 }
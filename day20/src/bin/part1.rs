@@ -21,4 +21,18 @@ mod tests {
         let text = fs::read_to_string("data/part1_example2.txt").unwrap();
         assert_eq!(part1(text), 11687500)
     }
+
+    #[test]
+    fn state_after_presses() {
+        use common::simulation::Simulation;
+        use day20::Machine;
+
+        let text = fs::read_to_string("data/part1_example2.txt").unwrap();
+        let mut machine = Machine::new(text.as_str());
+        machine.run_until(|machine| machine.presses() == 1 && machine.is_idle());
+        let state = machine.snapshot();
+        assert_eq!((state.low_pulses, state.high_pulses), (4, 4));
+        assert_eq!(state.flip_flops.get("a"), Some(&true));
+        assert_eq!(state.flip_flops.get("b"), Some(&true));
+    }
 }
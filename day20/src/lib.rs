@@ -1,15 +1,16 @@
+use common::simulation::Simulation;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, multispace1},
-    combinator::opt,
+    character::complete::{alpha1, char, line_ending},
+    combinator::map,
     multi::separated_list1,
-    sequence::tuple,
-    IResult,
+    sequence::{pair, preceded},
 };
 use num::integer::lcm;
+use parsing::{parse_all, IResult, Located};
 use petgraph::graphmap::DiGraphMap;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 #[derive(Debug, Clone, Copy, Hash)]
 enum Module {
@@ -25,36 +26,28 @@ struct Config<'a> {
     module_type_map: HashMap<&'a str, Module>,
 }
 
-fn parse_module(text: &str) -> IResult<&str, (&str, Module, Vec<&str>)> {
-    let (text, (module_type, name)) =
-        tuple((alt((tag("broadcaster"), tag("%"), tag("&"))), opt(alpha1)))(text)?;
-    let (text, _) = tag(" -> ")(text)?;
-    let (text, outputs) = separated_list1(tag(", "), alpha1)(text)?;
-    let module = match module_type {
-        "broadcaster" => Module::Broadcaster,
-        "%" => Module::FlipFlop(false),
-        "&" => Module::Conjunction,
-        _ => panic!("Failed to parse module type"),
-    };
-    Ok((
-        text,
-        (
-            match name {
-                Some(name) => name,
-                None => "broadcaster",
-            },
-            module,
-            outputs,
-        ),
-    ))
+/// `broadcaster`, `%name` or `&name`, then ` -> ` and the outputs
+fn parse_module(text: &str) -> IResult<'_, ((&str, Module), Vec<&str>)> {
+    pair(
+        alt((
+            map(tag("broadcaster"), |name| (name, Module::Broadcaster)),
+            map(preceded(char('%'), alpha1), |name| {
+                (name, Module::FlipFlop(false))
+            }),
+            map(preceded(char('&'), alpha1), |name| {
+                (name, Module::Conjunction)
+            }),
+        )),
+        preceded(tag(" -> "), separated_list1(tag(", "), alpha1)),
+    )(text)
 }
 
-fn parse_input(text: &str) -> IResult<&str, Config> {
-    let (text, modules) = separated_list1(multispace1, parse_module)(text)?;
+fn parse_input(text: &str) -> Result<Config<'_>, Located> {
+    let modules = parse_all(text, separated_list1(line_ending, parse_module))?;
     let mut config = DiGraphMap::new();
     let mut module_type_map = HashMap::new();
 
-    for (node, module_type, outputs) in modules {
+    for ((node, module_type), outputs) in modules {
         config.add_node(node);
         module_type_map.insert(node, module_type);
         for out_node in outputs {
@@ -73,73 +66,153 @@ fn parse_input(text: &str) -> IResult<&str, Config> {
     // Add new rx module
     module_type_map.insert("rx", Module::Rx);
 
-    Ok((
-        text,
-        Config {
-            graph: config,
-            module_type_map,
-        },
-    ))
+    Ok(Config {
+        graph: config,
+        module_type_map,
+    })
 }
 
-pub fn part1(text: String) -> u64 {
-    let (_, parsed_config) = parse_input(text.as_str()).unwrap();
-    let mut config = parsed_config.graph;
-    let mut module_type_map = parsed_config.module_type_map;
-    let mut low_pulses = 0;
-    let mut high_pulses = 0;
-    for _ in 0..1000 {
-        let mut pulses = VecDeque::new();
-        pulses.push_back(("button", "broadcaster", false));
-        while let Some((from_node, to_node, pulse)) = pulses.pop_front() {
-            // Count the pulse
-            if pulse {
-                high_pulses += 1;
-            } else {
-                low_pulses += 1;
-            }
+/// The module network, delivering one pulse per step and pressing the button
+/// whenever there are no pulses left in flight
+#[derive(Clone)]
+pub struct Machine<'a> {
+    config: DiGraphMap<&'a str, bool>,
+    module_type_map: HashMap<&'a str, Module>,
+    pulses: VecDeque<(&'a str, &'a str, bool)>,
+    last_pulse: Option<(&'a str, &'a str, bool)>,
+    presses: u64,
+    low_pulses: u64,
+    high_pulses: u64,
+}
 
-            // Update last pulse
-            let edge_weight_ref = config
-                .edge_weight_mut(from_node, to_node)
-                .unwrap_or_else(|| {
-                    panic!("Could not find edge between {} and {}", from_node, to_node,)
-                });
-            *edge_weight_ref = pulse;
-
-            // Figure out the outgoing pulse
-            let out_pulse = match module_type_map.get_mut(to_node) {
-                Some(Module::Button) => Some(false),
-                Some(Module::Broadcaster) => Some(pulse),
-                Some(Module::FlipFlop(state)) => {
-                    if pulse {
-                        None
-                    } else {
-                        *state = !*state;
-                        Some(*state)
-                    }
-                }
-                Some(Module::Conjunction) => Some(
-                    !config
-                        .edges_directed(to_node, petgraph::Direction::Incoming)
-                        .all(|(_, _, in_edge)| *in_edge),
-                ),
-                Some(Module::Rx) | None => {
-                    continue;
+/// How many times the button has been pressed, the pulses sent so far and
+/// which flip flops are on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MachineState {
+    pub presses: u64,
+    pub low_pulses: u64,
+    pub high_pulses: u64,
+    pub flip_flops: BTreeMap<String, bool>,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let config = parse_input(text).expect("Failed to parse.");
+        Self {
+            config: config.graph,
+            module_type_map: config.module_type_map,
+            pulses: VecDeque::new(),
+            last_pulse: None,
+            presses: 0,
+            low_pulses: 0,
+            high_pulses: 0,
+        }
+    }
+
+    pub fn presses(&self) -> u64 {
+        self.presses
+    }
+
+    /// Whether every pulse from the last button press has been delivered
+    pub fn is_idle(&self) -> bool {
+        self.pulses.is_empty()
+    }
+
+    /// The pulse delivered by the last step as (from, to, high)
+    pub fn last_pulse(&self) -> Option<(&'a str, &'a str, bool)> {
+        self.last_pulse
+    }
+}
+
+impl Simulation for Machine<'_> {
+    type Snapshot = MachineState;
+
+    fn step(&mut self) -> bool {
+        if self.pulses.is_empty() {
+            self.pulses.push_back(("button", "broadcaster", false));
+            self.presses += 1;
+        }
+        let (from_node, to_node, pulse) = self.pulses.pop_front().unwrap();
+        self.last_pulse = Some((from_node, to_node, pulse));
+
+        // Count the pulse
+        if pulse {
+            self.high_pulses += 1;
+        } else {
+            self.low_pulses += 1;
+        }
+
+        // Update last pulse
+        let edge_weight_ref = self
+            .config
+            .edge_weight_mut(from_node, to_node)
+            .unwrap_or_else(|| {
+                panic!("Could not find edge between {} and {}", from_node, to_node,)
+            });
+        *edge_weight_ref = pulse;
+
+        // Figure out the outgoing pulse
+        let out_pulse = match self.module_type_map.get_mut(to_node) {
+            Some(Module::Button) => Some(false),
+            Some(Module::Broadcaster) => Some(pulse),
+            Some(Module::FlipFlop(state)) => {
+                if pulse {
+                    None
+                } else {
+                    *state = !*state;
+                    Some(*state)
                 }
-            };
-
-            // Send the outgoing pulse
-            if let Some(out_pulse) = out_pulse {
-                config
-                    .neighbors_directed(to_node, petgraph::Direction::Outgoing)
-                    .for_each(|out| {
-                        pulses.push_back((to_node, out, out_pulse));
-                    });
             }
+            Some(Module::Conjunction) => Some(
+                !self
+                    .config
+                    .edges_directed(to_node, petgraph::Direction::Incoming)
+                    .all(|(_, _, in_edge)| *in_edge),
+            ),
+            Some(Module::Rx) | None => None,
+        };
+
+        // Send the outgoing pulse
+        if let Some(out_pulse) = out_pulse {
+            self.config
+                .neighbors_directed(to_node, petgraph::Direction::Outgoing)
+                .for_each(|out| {
+                    self.pulses.push_back((to_node, out, out_pulse));
+                });
+        }
+        true
+    }
+
+    fn snapshot(&self) -> MachineState {
+        MachineState {
+            presses: self.presses,
+            low_pulses: self.low_pulses,
+            high_pulses: self.high_pulses,
+            flip_flops: self
+                .module_type_map
+                .iter()
+                .filter_map(|(name, module)| match module {
+                    Module::FlipFlop(state) => Some((name.to_string(), *state)),
+                    _ => None,
+                })
+                .collect(),
         }
     }
-    low_pulses * high_pulses
+}
+
+pub const PART1_PRESSES: u64 = 1000;
+
+pub fn part1(text: String) -> u64 {
+    pulse_product(text, PART1_PRESSES)
+}
+
+/// The number of low pulses times the number of high pulses sent after
+/// pressing the button `presses` times
+pub fn pulse_product(text: String, presses: u64) -> u64 {
+    let mut machine = Machine::new(text.as_str());
+    machine.run_until(|machine| machine.presses() == presses && machine.is_idle());
+    let state = machine.snapshot();
+    state.low_pulses * state.high_pulses
 }
 
 // On inspection, the input graph consists of 4 large cycles.
@@ -154,86 +227,38 @@ pub fn part1(text: String) -> u64 {
 // This low pulse then gets inverted by a 1 input conjunction module so
 // a high pulse gets send to "ls".
 pub fn part2(text: String) -> u64 {
-    let (_, parsed_config) = parse_input(text.as_str()).unwrap();
-    let mut config = parsed_config.graph;
-    let mut module_type_map = parsed_config.module_type_map;
-    let mut num_presses = 0;
-    let rx_inputs: Vec<&str> = config
+    let mut machine = Machine::new(text.as_str());
+    let rx_inputs: Vec<&str> = machine
+        .config
         .neighbors_directed("rx", petgraph::Direction::Incoming)
         .collect();
     assert!(rx_inputs.len() == 1);
     let last_conjunction = rx_inputs[0];
     assert!(matches!(
-        module_type_map.get(last_conjunction).unwrap(),
+        machine.module_type_map.get(last_conjunction).unwrap(),
         Module::Conjunction
     ));
-    let num_last_conj_inputs = config
+    let num_last_conj_inputs = machine
+        .config
         .edges_directed(last_conjunction, petgraph::Direction::Incoming)
         .count();
     let mut last_conj_inputs = HashMap::new();
     let mut last_conj_cycle_lengths = HashMap::new();
 
     while last_conj_cycle_lengths.len() < num_last_conj_inputs {
-        let mut pulses = VecDeque::new();
-        pulses.push_back(("button", "broadcaster", false));
-        num_presses += 1;
-        while let Some((from_node, to_node, pulse)) = pulses.pop_front() {
-            // Update last pulse
-            let edge_weight_ref = config
-                .edge_weight_mut(from_node, to_node)
-                .unwrap_or_else(|| {
-                    panic!("Could not find edge between {} and {}", from_node, to_node,)
-                });
-            *edge_weight_ref = pulse;
-
-            // Figure out the outgoing pulse
-            let out_pulse = match module_type_map.get_mut(to_node) {
-                Some(Module::Button) => Some(false),
-                Some(Module::Broadcaster) => Some(pulse),
-                Some(Module::FlipFlop(state)) => {
-                    if pulse {
-                        None
-                    } else {
-                        *state = !*state;
-                        Some(*state)
-                    }
-                }
-                Some(Module::Conjunction) => {
-                    if to_node == last_conjunction && pulse {
-                        let last_conj_input = last_conj_inputs.get(from_node);
-                        if let Some(previous_num_presses) = last_conj_input {
-                            last_conj_cycle_lengths
-                                .insert(from_node, num_presses - previous_num_presses);
-                        } else {
-                            last_conj_inputs.insert(from_node, num_presses);
-                        }
-                    }
-                    Some(
-                        !config
-                            .edges_directed(to_node, petgraph::Direction::Incoming)
-                            .all(|(_, _, in_edge)| *in_edge),
-                    )
-                }
-                Some(Module::Rx) => {
-                    if pulse {
-                        continue;
-                    } else {
-                        return num_presses;
-                    }
-                }
-                None => {
-                    continue;
+        machine.step();
+        let num_presses = machine.presses();
+        match machine.last_pulse() {
+            Some((from_node, to_node, true)) if to_node == last_conjunction => {
+                let last_conj_input = last_conj_inputs.get(from_node);
+                if let Some(previous_num_presses) = last_conj_input {
+                    last_conj_cycle_lengths.insert(from_node, num_presses - previous_num_presses);
+                } else {
+                    last_conj_inputs.insert(from_node, num_presses);
                 }
-            };
-
-            // Send the outgoing pulse
-            if let Some(out_pulse) = out_pulse {
-                config
-                    .neighbors_directed(to_node, petgraph::Direction::Outgoing)
-                    .for_each(|out| {
-                        pulses.push_back((to_node, out, out_pulse));
-                    });
             }
+            Some((_, "rx", false)) => return num_presses,
+            _ => {}
         }
     }
     last_conj_cycle_lengths
@@ -1,9 +1,10 @@
 use std::fs;
-use day21::part1;
+use common::simulation::Simulation;
+use day21::{part1, Frontier, PART1_STEPS};
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text, 64));
+    println!("{}", part1(text, PART1_STEPS));
 }
 
 #[cfg(test)]
@@ -15,4 +16,19 @@ mod tests {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
         assert_eq!(part1(text, 6), 16)
     }
+
+    #[test]
+    fn frontier() {
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let grid = day21::parse_grid(text.clone());
+        let mut frontier = Frontier::new(&grid, &grid.start(), 0, 0);
+        assert_eq!(frontier.run_until(|frontier| frontier.steps() == 6), 6);
+        assert_eq!(frontier.reachable(), 16);
+        assert_eq!(frontier.snapshot().len(), 16);
+        frontier.run_until(|_| false);
+        // Every plot is reached by now, so any even number of steps past it
+        // counts the same plots
+        let steps = frontier.steps() + frontier.steps() % 2;
+        assert_eq!(frontier.reachable() as u32, part1(text, steps));
+    }
 }
@@ -1,10 +1,10 @@
 use std::fs;
-use day21::part2;
+use day21::{part2, PART2_STEPS};
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
     // println!("{}", part2(text.clone(), 64));
     // println!("{}", part2(text.clone(), 131*2+65));
     // println!("{}", part2(text.clone(), 131*3+65));
-    println!("{}", part2(text.clone(), 26501365));
+    println!("{}", part2(text.clone(), PART2_STEPS));
 }
@@ -1,16 +1,23 @@
 use colored::Colorize;
+use common::search::{reachable, SearchProblem};
+use common::simulation::Simulation;
 use std::collections::HashSet;
 use glam::UVec2;
+use parsing::{char_grid, parse_all};
 use itertools::{Itertools, MinMaxResult};
 
 #[derive(Debug)]
-struct Grid {
+pub struct Grid {
     start: UVec2,
     bounds: UVec2,
     rocks: HashSet<UVec2>,
 }
 
 impl Grid {
+    pub fn start(&self) -> UVec2 {
+        self.start
+    }
+
     /// Convert a location into a location within the bounds of the grid
     fn grid_bounded(&self, loc: &UVec2) -> UVec2 {
         UVec2::new(
@@ -41,38 +48,79 @@ impl Grid {
     }
 }
 
-fn parse_grid(text: String) -> Grid {
+pub fn parse_grid(text: String) -> Grid {
+    let garden = parse_all(
+        text.as_str(),
+        char_grid(|c| match c {
+            '.' | '#' | 'S' => Some(c),
+            _ => None,
+        }),
+    )
+    .unwrap();
     let mut grid = Grid {
         start: UVec2::ZERO,
-        bounds: UVec2::new(
-            text.lines().next().unwrap().len() as u32,
-            text.lines().count() as u32,
-        ),
+        bounds: UVec2::new(garden.width as u32, garden.height as u32),
         rocks: HashSet::new(),
     };
-    for (y, row) in text.lines().enumerate() {
-        for (x, c) in row.chars().enumerate() {
-            let loc = UVec2::new(x as u32, y as u32);
-            match c {
-                '.' => {}
-                '#' => {
-                    grid.rocks.insert(loc);
-                }
-                'S' => {
-                    grid.start = loc;
-                }
-                _ => panic!("Parsed bad character"),
+    for (x, y, &c) in garden.iter() {
+        let loc = UVec2::new(x as u32, y as u32);
+        match c {
+            '#' => {
+                grid.rocks.insert(loc);
+            }
+            'S' => {
+                grid.start = loc;
             }
+            _ => {}
         }
     }
     grid
 }
 
+/// Steps the elf takes in each part
+pub const PART1_STEPS: u32 = 64;
+pub const PART2_STEPS: u32 = 26501365;
+
 pub fn part1(text: String, num_steps: u32) -> u32 {
     let grid = parse_grid(text);
     count_plots_with_bounded_grid(&grid, num_steps, &grid.start, 0, num_steps % 2)
 }
 
+/// Walking between garden plots without leaving the grid
+struct Plots<'a> {
+    grid: &'a Grid,
+    start: UVec2,
+}
+
+impl SearchProblem for Plots<'_> {
+    type State = UVec2;
+
+    fn start(&self) -> UVec2 {
+        self.start
+    }
+
+    fn successors(&self, plot: &UVec2) -> Vec<(UVec2, u64)> {
+        [
+            plot.wrapping_add(UVec2::new(1, 0)),
+            plot.wrapping_sub(UVec2::new(1, 0)),
+            plot.wrapping_add(UVec2::new(0, 1)),
+            plot.wrapping_sub(UVec2::new(0, 1)),
+        ]
+        .into_iter()
+        .filter(|loc| {
+            loc.x < self.grid.bounds.x && loc.y < self.grid.bounds.y && !self.grid.rocks.contains(loc)
+        })
+        .map(|loc| (loc, 1))
+        .collect()
+    }
+
+    fn is_goal(&self, _plot: &UVec2) -> bool {
+        false
+    }
+}
+
+/// Plots first reached on a step with the final parity can be reached at the
+/// end by stepping back and forth
 fn count_plots_with_bounded_grid(
     grid: &Grid,
     num_steps: u32,
@@ -80,42 +128,93 @@ fn count_plots_with_bounded_grid(
     start_parity: u32,
     final_parity: u32,
 ) -> u32 {
-    let mut final_plots = HashSet::new();
-    let mut rejected_plots: HashSet<UVec2> = HashSet::new();
-    let mut current_plots = HashSet::new();
-    current_plots.insert(*start);
-    if start_parity == final_parity {
-        final_plots.insert(*start);
+    let plots = Plots {
+        grid,
+        start: *start,
+    };
+    reachable(&plots, num_steps as usize)
+        .depths
+        .into_values()
+        .filter(|&depth| (depth as u32 + start_parity) % 2 == final_parity)
+        .count() as u32
+}
+
+/// The set of plots an elf can reach, growing by one step at a time
+///
+/// Plots are only counted when they're reached on a step with the final parity
+/// since the elf can always step back and forth to come back to them. The
+/// answers come from the shared BFS, this is for watching the walk unfold.
+pub struct Frontier<'a> {
+    plots: Plots<'a>,
+    steps: u32,
+    start_parity: u32,
+    final_parity: u32,
+    final_plots: HashSet<UVec2>,
+    rejected_plots: HashSet<UVec2>,
+    current_plots: HashSet<UVec2>,
+}
+
+impl<'a> Frontier<'a> {
+    pub fn new(grid: &'a Grid, start: &UVec2, start_parity: u32, final_parity: u32) -> Self {
+        let mut final_plots = HashSet::new();
+        let mut rejected_plots = HashSet::new();
+        if start_parity == final_parity {
+            final_plots.insert(*start);
+        } else {
+            rejected_plots.insert(*start);
+        }
+        Self {
+            plots: Plots {
+                grid,
+                start: *start,
+            },
+            steps: 0,
+            start_parity,
+            final_parity,
+            final_plots,
+            rejected_plots,
+            current_plots: HashSet::from([*start]),
+        }
     }
-    for i in 0..num_steps {
-        let mut next_plots = HashSet::new();
-        for plot in current_plots {
-            next_plots.extend(
-                [
-                    plot + UVec2::new(1, 0),
-                    plot - UVec2::new(1, 0),
-                    plot + UVec2::new(0, 1),
-                    plot - UVec2::new(0, 1),
-                ]
-                .into_iter()
-                .filter(|loc| {
-                        loc.x < grid.bounds.x
-                        && loc.y < grid.bounds.y
-                        && !grid.rocks.contains(loc)
-                        && !final_plots.contains(loc)
-                        && !rejected_plots.contains(loc)
-                }),
-            );
+
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    pub fn reachable(&self) -> usize {
+        self.final_plots.len()
+    }
+}
+
+impl Simulation for Frontier<'_> {
+    type Snapshot = HashSet<UVec2>;
+
+    /// Runs to completion once a step reaches no new plots
+    fn step(&mut self) -> bool {
+        if self.current_plots.is_empty() {
+            return false;
         }
-        if (i + 1 + start_parity) % 2 == final_parity {
-            final_plots.extend(next_plots.iter());
+        let next_plots: HashSet<UVec2> = self
+            .current_plots
+            .iter()
+            .flat_map(|plot| self.plots.successors(plot))
+            .map(|(loc, _)| loc)
+            .filter(|loc| !self.final_plots.contains(loc) && !self.rejected_plots.contains(loc))
+            .collect();
+        if (self.steps + 1 + self.start_parity) % 2 == self.final_parity {
+            self.final_plots.extend(next_plots.iter());
         } else {
-            rejected_plots.extend(next_plots.iter());
+            self.rejected_plots.extend(next_plots.iter());
         }
-        current_plots = next_plots;
+        self.current_plots = next_plots;
+        self.steps += 1;
+        true
+    }
+
+    /// The plots reachable with the final parity so far
+    fn snapshot(&self) -> HashSet<UVec2> {
+        self.final_plots.clone()
     }
-    // print_plots(grid, &final_plots);
-    final_plots.len() as u32
 }
 
 #[allow(dead_code)]
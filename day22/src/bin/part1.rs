@@ -15,4 +15,18 @@ mod tests {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
         assert_eq!(part1(text), 5)
     }
+
+    #[test]
+    fn settled_positions() {
+        use common::simulation::Simulation;
+        use day22::{parse_bricks, sort_bricks, Settling};
+
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let (_, bricks) = parse_bricks(text.as_str()).unwrap();
+        let mut settling = Settling::new(sort_bricks(bricks));
+        assert_eq!(settling.run_until(|_| false), 7);
+        let settled = settling.snapshot();
+        assert_eq!((settled[0].start.z, settled[0].end.z), (1, 1));
+        assert_eq!((settled[6].start.z, settled[6].end.z), (5, 6));
+    }
 }
@@ -1,19 +1,20 @@
 use std::collections::{HashMap, HashSet};
 
+use common::simulation::Simulation;
 use glam::{IVec2, IVec3};
 use itertools::Itertools;
 use nom::{
-    character::complete::{char, digit1, line_ending},
+    character::complete::{char, line_ending},
     combinator::map,
     multi::separated_list1,
-    sequence::{separated_pair, tuple},
-    IResult,
+    sequence::separated_pair,
 };
+use parsing::{parse_all, vector, IResult};
 
-#[derive(Debug)]
-struct Brick {
-    start: IVec3,
-    end: IVec3,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Brick {
+    pub start: IVec3,
+    pub end: IVec3,
 }
 
 impl Brick {
@@ -36,20 +37,11 @@ impl Brick {
     }
 }
 
-fn parse_vec(text: &str) -> IResult<&str, IVec3> {
-    map(
-        tuple((digit1, char(','), digit1, char(','), digit1)),
-        |(x, _, y, _, z): (&str, char, &str, char, &str)| {
-            IVec3::new(
-                x.parse::<i32>().unwrap(),
-                y.parse::<i32>().unwrap(),
-                z.parse::<i32>().unwrap(),
-            )
-        },
-    )(text)
+fn parse_vec(text: &str) -> IResult<'_, IVec3> {
+    map(vector, IVec3::from_array)(text)
 }
 
-fn parse_bricks(text: &str) -> IResult<&str, Vec<Brick>> {
+pub fn parse_bricks(text: &str) -> IResult<'_, Vec<Brick>> {
     separated_list1(
         line_ending,
         map(
@@ -65,20 +57,51 @@ fn parse_bricks(text: &str) -> IResult<&str, Vec<Brick>> {
     )(text)
 }
 
-pub fn part1(text: String) -> usize {
-    let (_, bricks) = parse_bricks(text.as_str()).unwrap();
-    let mut height_map: HashMap<IVec2, (i32, Option<usize>)> = HashMap::new();
-    let mut support_map: HashMap<usize, HashSet<usize>> = HashMap::new();
-    for (idx, brick) in bricks
+/// Sorts the bricks by their starting height so they can be dropped in order
+pub fn sort_bricks(bricks: Vec<Brick>) -> Vec<Brick> {
+    bricks
         .into_iter()
         .sorted_by_key(|brick| brick.start.z)
-        .enumerate()
-    {
+        .collect()
+}
+
+/// Sorted bricks falling into place, one brick per step
+pub struct Settling {
+    bricks: Vec<Brick>,
+    settled: Vec<Brick>,
+    height_map: HashMap<IVec2, (i32, Option<usize>)>,
+    support_map: HashMap<usize, HashSet<usize>>,
+}
+
+impl Settling {
+    pub fn new(bricks: Vec<Brick>) -> Self {
+        Self {
+            bricks,
+            settled: Vec::new(),
+            height_map: HashMap::new(),
+            support_map: HashMap::new(),
+        }
+    }
+
+    /// Which bricks each settled brick is resting on
+    pub fn support_map(&self) -> &HashMap<usize, HashSet<usize>> {
+        &self.support_map
+    }
+}
+
+impl Simulation for Settling {
+    type Snapshot = Vec<Brick>;
+
+    fn step(&mut self) -> bool {
+        let idx = self.settled.len();
+        let Some(brick) = self.bricks.get(idx) else {
+            return false;
+        };
         let cubes = brick.get_cubes();
         let landing_height = cubes
             .iter()
             .map(|cube| {
-                height_map
+                self.height_map
                     .get(&IVec2::new(cube.x, cube.y))
                     .unwrap_or(&(0, None))
                     .0
@@ -87,7 +110,8 @@ pub fn part1(text: String) -> usize {
             .unwrap();
         let mut supported_by = HashSet::new();
         for cube in cubes {
-            let height_map_entry = height_map
+            let height_map_entry = self
+                .height_map
                 .entry(IVec2::new(cube.x, cube.y))
                 .or_insert((0, None));
             if height_map_entry.0 == landing_height {
@@ -100,8 +124,32 @@ pub fn part1(text: String) -> usize {
             let brick_height = (brick.end - brick.start).z;
             *height_map_entry = (landing_height + brick_height + 1, Some(idx));
         }
-        support_map.insert(idx, supported_by);
+        self.support_map.insert(idx, supported_by);
+        // The ground is at z = 0 so the lowest a brick can rest is z = 1
+        let drop = IVec3::new(0, 0, brick.start.z - landing_height - 1);
+        self.settled.push(Brick {
+            start: brick.start - drop,
+            end: brick.end - drop,
+        });
+        true
+    }
+
+    /// The bricks that have come to rest so far, in their final positions
+    fn snapshot(&self) -> Vec<Brick> {
+        self.settled.clone()
     }
+}
+
+/// Drops the sorted bricks and records which bricks each one comes to rest on
+pub fn support_map(bricks: &[Brick]) -> HashMap<usize, HashSet<usize>> {
+    let mut settling = Settling::new(bricks.to_vec());
+    settling.run_until(|_| false);
+    settling.support_map
+}
+
+pub fn part1(text: String) -> usize {
+    let bricks = parse_all(text.as_str(), parse_bricks).unwrap();
+    let support_map = support_map(&sort_bricks(bricks));
     support_map.len()
         - support_map
             .into_iter()
@@ -117,42 +165,8 @@ pub fn part1(text: String) -> usize {
 }
 
 pub fn part2(text: String) -> usize {
-    let (_, bricks) = parse_bricks(text.as_str()).unwrap();
-    let mut height_map: HashMap<IVec2, (i32, Option<usize>)> = HashMap::new();
-    let mut support_map: HashMap<usize, HashSet<usize>> = HashMap::new();
-    for (idx, brick) in bricks
-        .into_iter()
-        .sorted_by_key(|brick| brick.start.z)
-        .enumerate()
-    {
-        let cubes = brick.get_cubes();
-        let landing_height = cubes
-            .iter()
-            .map(|cube| {
-                height_map
-                    .get(&IVec2::new(cube.x, cube.y))
-                    .unwrap_or(&(0, None))
-                    .0
-            })
-            .max()
-            .unwrap();
-        let mut supported_by = HashSet::new();
-        for cube in cubes {
-            let height_map_entry = height_map
-                .entry(IVec2::new(cube.x, cube.y))
-                .or_insert((0, None));
-            if height_map_entry.0 == landing_height {
-                if let Some(other_idx) = height_map_entry.1 {
-                    if other_idx != idx {
-                        supported_by.insert(other_idx);
-                    }
-                }
-            }
-            let brick_height = (brick.end - brick.start).z;
-            *height_map_entry = (landing_height + brick_height + 1, Some(idx));
-        }
-        support_map.insert(idx, supported_by);
-    }
+    let bricks = parse_all(text.as_str(), parse_bricks).unwrap();
+    let support_map = support_map(&sort_bricks(bricks));
     let unsafe_bricks = support_map
         .iter()
         .filter_map(|(_, supporters)| {
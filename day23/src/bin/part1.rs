@@ -1,18 +1,60 @@
+use common::checkpoint::Checkpoint;
+use common::context::{BarProgress, SolverContext};
+use day23::part1_with_context;
+use std::env;
 use std::fs;
-use day23::part1;
+use std::sync::Arc;
+use std::time::Duration;
 
+/// Pass a file name to save the search there every 30 seconds and resume it
+/// from there if it's interrupted
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text));
+    let mut ctx = SolverContext::new().with_progress(Arc::new(BarProgress::new()));
+    if let Some(path) = env::args().nth(1) {
+        ctx = ctx.with_checkpoint(Checkpoint::new(path, Duration::from_secs(30)));
+    }
+    println!("{}", part1_with_context(text, &ctx).unwrap());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day23::part1;
 
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
         assert_eq!(part1(text), 94)
     }
+
+    #[test]
+    fn resume_from_checkpoint() {
+        use common::context::{CancellationToken, Progress};
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        struct CancelAfter(AtomicU64, CancellationToken);
+        impl Progress for CancelAfter {
+            fn advance(&self, delta: u64) {
+                if self.0.fetch_sub(delta, Ordering::Relaxed) <= delta {
+                    self.1.cancel();
+                }
+            }
+        }
+
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day23-part1.json");
+        let cancel = CancellationToken::new();
+        let ctx = SolverContext::new()
+            .with_cancellation(cancel.clone())
+            .with_progress(Arc::new(CancelAfter(AtomicU64::new(50), cancel)))
+            .with_checkpoint(Checkpoint::new(&path, Duration::from_secs(30)));
+        assert!(part1_with_context(text.clone(), &ctx).is_err());
+        assert!(path.exists());
+        let ctx =
+            SolverContext::new().with_checkpoint(Checkpoint::new(&path, Duration::from_secs(30)));
+        assert_eq!(part1_with_context(text, &ctx).unwrap(), 94);
+        assert!(!path.exists());
+    }
 }
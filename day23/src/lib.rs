@@ -1,8 +1,13 @@
 use core::fmt;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use colored::Colorize;
+use common::checkpoint::SearchError;
+use common::context::{BarProgress, Interrupted, SolverContext};
+use common::search::{LongestPath, SearchProblem};
 use glam::IVec2;
+use parsing::{char_grid, parse_all};
 use petgraph::{algo::all_simple_paths, Graph};
 
 #[derive(Debug, PartialEq, Eq)]
@@ -50,7 +55,7 @@ impl fmt::Debug for Grid {
 
 impl Grid {
     #[allow(dead_code)]
-    fn show_path(&self, path: &Path) {
+    fn show_path(&self, path: &[IVec2]) {
         println!();
         for y in 0..self.bounds.y {
             for x in 0..self.bounds.x {
@@ -64,7 +69,7 @@ impl Grid {
                     Tile::Slope(Direction::South) => "v",
                     Tile::Slope(Direction::West) => "<",
                 };
-                if path.visited.contains(&loc) {
+                if path.contains(&loc) {
                     print!("{}", tile_char.green());
                 } else {
                     print!("{}", tile_char);
@@ -94,127 +99,131 @@ impl Grid {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Path {
-    visited: HashSet<IVec2>,
-    current: IVec2,
+fn parse_grid(text: &str) -> Grid {
+    let map = parse_all(
+        text,
+        char_grid(|c| match c {
+            '.' => Some(Tile::Path),
+            '#' => Some(Tile::Forest),
+            '^' => Some(Tile::Slope(Direction::North)),
+            '>' => Some(Tile::Slope(Direction::East)),
+            'v' => Some(Tile::Slope(Direction::South)),
+            '<' => Some(Tile::Slope(Direction::West)),
+            _ => None,
+        }),
+    )
+    .unwrap();
+    let bounds = IVec2::new(map.width as i32, map.height as i32);
+    let tiles = map
+        .cells
+        .into_iter()
+        .enumerate()
+        .map(|(idx, tile)| {
+            (
+                IVec2::new(idx as i32 % bounds.x, idx as i32 / bounds.x),
+                tile,
+            )
+        })
+        .collect();
+    Grid { bounds, tiles }
 }
 
-fn parse_grid(text: String) -> Grid {
-    let mut grid = Grid {
-        bounds: IVec2::new(
-            text.lines().next().unwrap().len() as i32,
-            text.lines().count() as i32,
-        ),
-        tiles: HashMap::new(),
-    };
-    for (y, row) in text.lines().enumerate() {
-        for (x, c) in row.chars().enumerate() {
-            let loc = IVec2::new(x as i32, y as i32);
-            grid.tiles.insert(
-                loc,
-                match c {
-                    '.' => Tile::Path,
-                    '#' => Tile::Forest,
-                    '^' => Tile::Slope(Direction::North),
-                    '>' => Tile::Slope(Direction::East),
-                    'v' => Tile::Slope(Direction::South),
-                    '<' => Tile::Slope(Direction::West),
-                    _ => panic!("Parsed bad character"),
-                },
-            );
-        }
-    }
-    grid
+/// Walking from the start to the end of the trail, only going down slopes
+/// in the direction they point
+struct Hike<'a> {
+    grid: &'a Grid,
+    start: IVec2,
+    end: IVec2,
 }
 
-pub fn part1(text: String) -> usize {
-    let grid = parse_grid(text);
-    let start = grid.get_start();
-    let end = grid.get_end();
-    let mut current_paths = Vec::<Path>::new();
-    let mut finished_paths = Vec::<Path>::new();
-    current_paths.push(Path {
-        visited: HashSet::new(),
-        current: start,
-    });
-    while let Some(path) = current_paths.pop() {
-        if path.current == end {
-            finished_paths.push(path);
-        } else {
-            [
-                path.current + IVec2::new(1, 0),
-                path.current - IVec2::new(1, 0),
-                path.current + IVec2::new(0, 1),
-                path.current - IVec2::new(0, 1),
-            ]
+impl SearchProblem for Hike<'_> {
+    type State = IVec2;
+
+    fn start(&self) -> IVec2 {
+        self.start
+    }
+
+    fn successors(&self, loc: &IVec2) -> Vec<(IVec2, u64)> {
+        let directions = match self.grid.tiles.get(loc) {
+            Some(Tile::Slope(Direction::North)) => vec![IVec2::new(0, -1)],
+            Some(Tile::Slope(Direction::South)) => vec![IVec2::new(0, 1)],
+            Some(Tile::Slope(Direction::East)) => vec![IVec2::new(1, 0)],
+            Some(Tile::Slope(Direction::West)) => vec![IVec2::new(-1, 0)],
+            _ => vec![
+                IVec2::new(1, 0),
+                IVec2::new(-1, 0),
+                IVec2::new(0, 1),
+                IVec2::new(0, -1),
+            ],
+        };
+        directions
             .into_iter()
-            .for_each(|adj_loc| {
-                if let Some(tile) = grid.tiles.get(&adj_loc) {
-                    let mut path = path.clone();
-                    match tile {
-                        Tile::Path => {
-                            if !path.visited.contains(&adj_loc) {
-                                path.visited.insert(adj_loc);
-                                path.current = adj_loc;
-                                current_paths.push(path);
-                            }
-                        }
-                        Tile::Forest => {}
-                        Tile::Slope(Direction::North) => {
-                            let next_loc = adj_loc - IVec2::new(0, 1);
-                            if !path.visited.contains(&adj_loc) && !path.visited.contains(&next_loc)
-                            {
-                                path.visited.insert(adj_loc);
-                                path.visited.insert(next_loc);
-                                path.current = next_loc;
-                                current_paths.push(path);
-                            }
-                        }
-                        Tile::Slope(Direction::South) => {
-                            let next_loc = adj_loc + IVec2::new(0, 1);
-                            if !path.visited.contains(&adj_loc) && !path.visited.contains(&next_loc)
-                            {
-                                path.visited.insert(adj_loc);
-                                path.visited.insert(next_loc);
-                                path.current = next_loc;
-                                current_paths.push(path);
-                            }
-                        }
-                        Tile::Slope(Direction::East) => {
-                            let next_loc = adj_loc + IVec2::new(1, 0);
-                            if !path.visited.contains(&adj_loc) && !path.visited.contains(&next_loc)
-                            {
-                                path.visited.insert(adj_loc);
-                                path.visited.insert(next_loc);
-                                path.current = next_loc;
-                                current_paths.push(path);
-                            }
-                        }
-                        Tile::Slope(Direction::West) => {
-                            let next_loc = adj_loc - IVec2::new(1, 0);
-                            if !path.visited.contains(&adj_loc) && !path.visited.contains(&next_loc)
-                            {
-                                path.visited.insert(adj_loc);
-                                path.visited.insert(next_loc);
-                                path.current = next_loc;
-                                current_paths.push(path);
-                            }
-                        }
-                    }
-                }
+            .map(|dir| *loc + dir)
+            .filter(|adj_loc| {
+                matches!(
+                    self.grid.tiles.get(adj_loc),
+                    Some(Tile::Path) | Some(Tile::Slope(_))
+                )
             })
+            .map(|adj_loc| (adj_loc, 1))
+            .collect()
+    }
+
+    fn is_goal(&self, loc: &IVec2) -> bool {
+        *loc == self.end
+    }
+}
+
+pub fn part1(text: String) -> usize {
+    part1_with_context(text, &SolverContext::new()).unwrap()
+}
+
+/// Depth first search over every path, which can be checkpointed by saving
+/// the paths still to be explored
+pub fn part1_with_context(text: String, ctx: &SolverContext) -> Result<usize, SearchError> {
+    let grid = parse_grid(&text);
+    let hike = Hike {
+        grid: &grid,
+        start: grid.get_start(),
+        end: grid.get_end(),
+    };
+    let saved = match ctx.checkpoint() {
+        Some(checkpoint) => checkpoint.load::<LongestPath<IVec2>>(&text)?,
+        None => None,
+    };
+    let mut search = saved.unwrap_or_else(|| LongestPath::new(&hike));
+    let save = |search: &LongestPath<IVec2>| match ctx.checkpoint() {
+        Some(checkpoint) => checkpoint.save(&text, search),
+        None => Ok(()),
+    };
+    ctx.progress().start(None);
+    loop {
+        if let Err(interrupted) = ctx.check() {
+            save(&search)?;
+            return Err(interrupted.into());
+        }
+        if ctx.checkpoint().is_some_and(|checkpoint| checkpoint.due()) {
+            save(&search)?;
+        }
+        if !search.expand(&hike) {
+            break;
         }
+        ctx.progress().advance(1);
     }
-    finished_paths
-        .into_iter()
-        .map(|path| path.visited.len())
-        .max()
-        .unwrap()
+    ctx.progress().finish();
+    if let Some(checkpoint) = ctx.checkpoint() {
+        checkpoint.clear()?;
+    }
+    Ok(search.into_solution().unwrap().cost as usize)
 }
 
 pub fn part2(text: String) -> usize {
-    let grid = parse_grid(text);
+    let ctx = SolverContext::new().with_progress(Arc::new(BarProgress::new()));
+    part2_with_context(text, &ctx).unwrap()
+}
+
+pub fn part2_with_context(text: String, ctx: &SolverContext) -> Result<usize, Interrupted> {
+    let grid = parse_grid(&text);
     let start = grid.get_start();
     let end = grid.get_end();
 
@@ -250,12 +259,19 @@ pub fn part2(text: String) -> usize {
             }
         })
     }
-    all_simple_paths(
+    ctx.progress().start(None);
+    let mut longest = 0;
+    for path in all_simple_paths::<Vec<_>, _>(
         &graph,
         *node_id_map.get(&start).unwrap(),
         *node_id_map.get(&end).unwrap(),
         1,
         None,
-    )
-    .max_by_key(|path: &Vec<_>| path.len()).unwrap().len() - 1
+    ) {
+        ctx.check()?;
+        ctx.progress().advance(1);
+        longest = longest.max(path.len() - 1);
+    }
+    ctx.progress().finish();
+    Ok(longest)
 }
@@ -1,12 +1,9 @@
-use day24::part1;
+use day24::{part1, PART1_MAX, PART1_MIN};
 use std::fs;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!(
-        "{}",
-        part1(text, 200000000000000.0, 400000000000000.0)
-    );
+    println!("{}", part1(text, PART1_MIN, PART1_MAX));
 }
 
 #[cfg(test)]
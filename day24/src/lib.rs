@@ -5,10 +5,9 @@ use nom::{
     character::complete::{line_ending, space1},
     combinator::map,
     multi::separated_list1,
-    number::complete::double,
-    sequence::{pair, separated_pair, tuple},
-    IResult,
+    sequence::{pair, separated_pair},
 };
+use parsing::{parse_all, vector, IResult};
 
 #[derive(Debug)]
 struct Hailstone {
@@ -59,20 +58,11 @@ impl Hailstone {
     }
 }
 
-fn coords(text: &str) -> IResult<&str, DVec3> {
-    map(
-        tuple((
-            double,
-            pair(tag(","), space1),
-            double,
-            pair(tag(","), space1),
-            double,
-        )),
-        |(x, _, y, _, z)| DVec3::new(x, y, z),
-    )(text)
+fn coords(text: &str) -> IResult<'_, DVec3> {
+    map(vector, DVec3::from_array)(text)
 }
 
-fn parse_hail(text: &str) -> IResult<&str, Vec<Hailstone>> {
+fn parse_hail(text: &str) -> IResult<'_, Vec<Hailstone>> {
     separated_list1(
         line_ending,
         map(
@@ -82,8 +72,12 @@ fn parse_hail(text: &str) -> IResult<&str, Vec<Hailstone>> {
     )(text)
 }
 
+/// The test area the hailstone paths have to cross inside, on both axes
+pub const PART1_MIN: f64 = 200000000000000.0;
+pub const PART1_MAX: f64 = 400000000000000.0;
+
 pub fn part1(text: String, min: f64, max: f64) -> usize {
-    let (_, hail) = parse_hail(text.as_str()).unwrap();
+    let hail = parse_all(text.as_str(), parse_hail).unwrap();
     let mut num_intersections = 0;
     for (stone1, stone2) in hail.iter().tuple_combinations() {
         let intersection = stone1.find_intersection_2d(stone2);
@@ -97,6 +91,6 @@ pub fn part1(text: String, min: f64, max: f64) -> usize {
 }
 
 pub fn part2(text: String) -> i64 {
-    let (_, hail) = parse_hail(text.as_str()).unwrap();
+    let hail = parse_all(text.as_str(), parse_hail).unwrap();
     todo!();
 }
@@ -1,18 +1,78 @@
+use common::checkpoint::Checkpoint;
+use common::context::{BarProgress, SolverContext};
+use day25::part1_with_context;
+use std::env;
 use std::fs;
-use day25::part1;
+use std::sync::Arc;
+use std::time::Duration;
 
+/// Pass a file name to save the search there every 30 seconds and resume it
+/// from there if it's interrupted
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text));
+    let mut ctx = SolverContext::new().with_progress(Arc::new(BarProgress::new()));
+    if let Some(path) = env::args().nth(1) {
+        ctx = ctx.with_checkpoint(Checkpoint::new(path, Duration::from_secs(30)));
+    }
+    println!("{}", part1_with_context(text, &ctx).unwrap());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day25::part1;
 
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
         assert_eq!(part1(text), 54)
     }
+
+    #[test]
+    fn resume_from_checkpoint() {
+        use common::checkpoint::SearchError;
+        use common::context::{CancellationToken, Progress};
+        use day25::Part1State;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        struct CancelAfter(AtomicU64, CancellationToken);
+        impl Progress for CancelAfter {
+            fn advance(&self, delta: u64) {
+                if self.0.fetch_sub(delta, Ordering::Relaxed) <= delta {
+                    self.1.cancel();
+                }
+            }
+        }
+
+        // The first wire has 496 combinations to search, the second 465 and
+        // the answer is only found from the third
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day25-part1.json");
+        let checkpoint = || Checkpoint::new(&path, Duration::from_secs(30));
+        let cancel = CancellationToken::new();
+        let ctx = SolverContext::new()
+            .with_cancellation(cancel.clone())
+            .with_progress(Arc::new(CancelAfter(AtomicU64::new(700), cancel)))
+            .with_checkpoint(checkpoint());
+        assert!(part1_with_context(text.clone(), &ctx).is_err());
+        assert_eq!(
+            checkpoint().load::<Part1State>(&text).unwrap(),
+            Some(Part1State { next: 1 })
+        );
+        let ctx = SolverContext::new().with_checkpoint(checkpoint());
+        assert_eq!(part1_with_context(text.clone(), &ctx).unwrap(), 54);
+        assert!(!path.exists());
+
+        checkpoint().save(&text, &Part1State { next: 2 }).unwrap();
+        let ctx = SolverContext::new().with_checkpoint(checkpoint());
+        assert_eq!(part1_with_context(text.clone(), &ctx).unwrap(), 54);
+        checkpoint().save(&text, &Part1State { next: 2 }).unwrap();
+        let ctx = SolverContext::new().with_checkpoint(checkpoint());
+        assert!(matches!(
+            part1_with_context("a: b\n".to_string(), &ctx),
+            Err(SearchError::Checkpoint(_))
+        ));
+        checkpoint().clear().unwrap();
+    }
 }
@@ -1,29 +1,27 @@
-use indicatif::ParallelProgressIterator;
+use common::checkpoint::SearchError;
+use common::context::{BarProgress, SolverContext};
 use itertools::Itertools;
 use nom::{
-    bytes::complete::tag,
     character::complete::{alpha1, line_ending, space1},
     multi::separated_list1,
-    IResult,
 };
+use parsing::{labelled, parse_all, Located};
 use petgraph::{
     algo::{connected_components, tarjan_scc},
-    graph::{DefaultIx, Graph, NodeIndex, UnGraph},
+    graph::{DefaultIx, EdgeIndex, Graph, NodeIndex, UnGraph},
     visit::{EdgeRef, IntoNodeReferences},
     Undirected,
 };
 use rayon::iter::{ParallelBridge, ParallelIterator};
+use serde::{Deserialize, Serialize};
 use std::collections::{hash_map::RandomState, HashMap, HashSet};
+use std::sync::Arc;
 
-fn parse_line(text: &str) -> IResult<&str, (&str, Vec<&str>)> {
-    let (text, component) = alpha1(text)?;
-    let (text, _) = tag(": ")(text)?;
-    let (text, connections) = separated_list1(space1, alpha1)(text)?;
-    Ok((text, (component, connections)))
-}
-
-fn parse(text: &str) -> IResult<&str, UnGraph<&str, usize>> {
-    let (text, lines) = separated_list1(line_ending, parse_line)(text)?;
+fn parse(text: &str) -> Result<UnGraph<&str, usize>, Located> {
+    let lines = parse_all(
+        text,
+        separated_list1(line_ending, labelled(separated_list1(space1, alpha1))),
+    )?;
     let mut graph = Graph::new_undirected();
     let mut node_map = HashMap::new();
     for (component, connections) in lines {
@@ -37,32 +35,103 @@ fn parse(text: &str) -> IResult<&str, UnGraph<&str, usize>> {
             graph.add_edge(node_id, other_node_id, 1);
         }
     }
-    Ok((text, graph))
+    Ok(graph)
+}
+
+pub fn edges(text: &str) -> Result<Vec<(&str, &str)>, Located> {
+    let graph = parse(text)?;
+    Ok(graph
+        .edge_references()
+        .map(|edge| (graph[edge.source()], graph[edge.target()]))
+        .collect())
 }
 
 // Pretty much brute force
 pub fn part1(text: String) -> usize {
-    let (_, graph) = parse(text.as_str()).unwrap();
-    graph
-        .edge_indices()
-        .combinations(3)
-        .par_bridge()
-        .progress_count(5600742476)
-        .find_map_any(|cut_wires| {
-            let mut graph = graph.clone();
-            graph.retain_edges(|_, edge| !cut_wires.contains(&edge));
-            if connected_components(&graph) == 2 {
-                Some(
-                    tarjan_scc(&graph)
+    let ctx = SolverContext::new().with_progress(Arc::new(BarProgress::new()));
+    part1_with_context(text, &ctx).unwrap()
+}
+
+/// What part 1 saves to its checkpoint
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Part1State {
+    /// Index of the first cut wire that hasn't been fully searched yet
+    pub next: usize,
+}
+
+/// Number of 3-combinations of `num_edges` edges whose lowest edge is `first`
+fn combinations_from(first: usize, num_edges: usize) -> u64 {
+    let rest = num_edges.saturating_sub(first + 1) as u64;
+    rest * rest.saturating_sub(1) / 2
+}
+
+/// Searches the wire combinations in chunks that share a first wire so the
+/// search can be checkpointed between chunks and resumed from the next one
+pub fn part1_with_context(text: String, ctx: &SolverContext) -> Result<usize, SearchError> {
+    let graph = parse(text.as_str()).expect("Failed to parse.");
+    let num_edges = graph.edge_count();
+    ctx.progress().start(Some(
+        (0..num_edges)
+            .map(|first| combinations_from(first, num_edges))
+            .sum(),
+    ));
+    let mut first = 0;
+    if let Some(checkpoint) = ctx.checkpoint() {
+        if let Some(state) = checkpoint.load::<Part1State>(&text)? {
+            first = state.next;
+            ctx.progress().advance(
+                (0..first)
+                    .map(|first| combinations_from(first, num_edges))
+                    .sum(),
+            );
+        }
+    }
+    let save = |next: usize| match ctx.checkpoint() {
+        Some(checkpoint) => checkpoint.save(&text, &Part1State { next }),
+        None => Ok(()),
+    };
+    while first < num_edges {
+        let result = (first + 1..num_edges)
+            .tuple_combinations()
+            .par_bridge()
+            .find_map_any(|(second, third)| {
+                if let Err(interrupted) = ctx.check() {
+                    return Some(Err(interrupted));
+                }
+                ctx.progress().advance(1);
+                let cut_wires = [first, second, third].map(EdgeIndex::new);
+                let mut graph = graph.clone();
+                graph.retain_edges(|_, edge| !cut_wires.contains(&edge));
+                if connected_components(&graph) == 2 {
+                    Some(Ok(tarjan_scc(&graph)
                         .into_iter()
                         .map(|comp| comp.len())
-                        .product(),
-                )
-            } else {
-                None
+                        .product()))
+                } else {
+                    None
+                }
+            });
+        match result {
+            Some(Ok(answer)) => {
+                ctx.progress().finish();
+                if let Some(checkpoint) = ctx.checkpoint() {
+                    checkpoint.clear()?;
+                }
+                return Ok(answer);
             }
-        })
-        .unwrap()
+            Some(Err(interrupted)) => {
+                save(first)?;
+                return Err(interrupted.into());
+            }
+            None => {
+                first += 1;
+                if ctx.checkpoint().is_some_and(|checkpoint| checkpoint.due()) {
+                    save(first)?;
+                }
+            }
+        }
+    }
+    panic!("No three wires split the graph in two")
 }
 
 fn connectivity<N>(
@@ -192,7 +261,7 @@ fn min_cut_phase(graph: UnGraph<Vec<&str>, usize>) -> CutOfThePhase {
 
 // Do part 1 again but implement the Stoer-Wagner algorithm
 pub fn part2(text: String) -> usize {
-    let (_, original_graph) = parse(text.as_str()).unwrap();
+    let original_graph = parse(text.as_str()).expect("Failed to parse.");
     let mut new_graph = Graph::<Vec<&str>, usize, Undirected>::new_undirected();
     let mut node_map = HashMap::new();
     for edge in original_graph.edge_references() {
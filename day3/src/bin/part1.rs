@@ -1,9 +1,23 @@
-use day3::part1;
-use std::fs;
+use day3::{part1, Neighborhood, PartGraph};
+use std::{env, fs};
 
+/// `--graph dot|json` prints how the part numbers and symbols connect instead
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text));
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let [flag, format] = &args[..] {
+        if flag == "--graph" {
+            let schematic = day3::parse_schematic(&text).expect("Failed to parse.");
+            let graph = PartGraph::new(&schematic, Neighborhood::Eight);
+            match format.as_str() {
+                "dot" => print!("{}", graph.dot()),
+                "json" => println!("{:#}", graph.json()),
+                _ => panic!("Unknown graph format {}", format),
+            }
+            return;
+        }
+    }
+    println!("{}", part1(text).unwrap());
 }
 
 #[cfg(test)]
@@ -13,6 +27,73 @@ mod tests {
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
-        assert_eq!(part1(text), 4361)
+        assert_eq!(part1(text).unwrap(), 4361)
+    }
+
+    #[test]
+    fn large() {
+        // The example tiled 100 times each way, with a gap so tiles don't touch
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let mut large = String::new();
+        for _ in 0..100 {
+            for line in text.lines() {
+                large.push_str(&format!("{}.", line).repeat(100));
+                large.push('\n');
+            }
+            large.push_str(&".".repeat(1100));
+            large.push('\n');
+        }
+        assert_eq!(part1(large).unwrap(), 4361 * 100 * 100)
+    }
+
+    #[test]
+    fn too_large() {
+        let err = day3::parse_schematic("..99999999999*\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert!(day3::parse_schematic("..4294967295*\n").is_ok());
+    }
+
+    #[test]
+    fn graph() {
+        let text = fs::read_to_string("data/part1_example.txt").unwrap();
+        let schematic = day3::parse_schematic(&text).unwrap();
+        let graph = PartGraph::new(&schematic, Neighborhood::Eight);
+        let ids = |nodes: Vec<petgraph::graph::NodeIndex>| -> Vec<usize> {
+            nodes.into_iter().map(|node| node.index()).collect()
+        };
+        let components: Vec<Vec<usize>> = graph.components().into_iter().map(ids).collect();
+        assert_eq!(
+            components,
+            vec![
+                vec![0, 2, 10],
+                vec![1],
+                vec![3, 11],
+                vec![4, 12],
+                vec![5],
+                vec![6, 13],
+                vec![7, 9, 15],
+                vec![8, 14]
+            ]
+        );
+        assert_eq!(ids(graph.orphan_numbers()), vec![1, 5]);
+        assert!(graph.lonely_symbols().is_empty());
+        let four = PartGraph::new(&schematic, Neighborhood::Four);
+        assert_eq!(ids(four.lonely_symbols()), vec![13]);
+
+        let json = graph.json();
+        assert_eq!(json["nodes"][10]["symbol"], "*");
+        assert_eq!(json["edges"].as_array().unwrap().len(), 8);
+        assert_eq!(json["orphan_numbers"], serde_json::json!([1, 5]));
+        let dot = graph.dot();
+        assert!(dot.starts_with("graph schematic {"));
+        assert!(dot.contains("    0 [label=\"467\" shape=box]\n"));
+        assert!(dot.contains("    0 -- 10\n"));
+
+        let schematic = day3::parse_schematic("1\\\u{2060}\n.\".\n").unwrap();
+        let dot = PartGraph::new(&schematic, Neighborhood::Eight).dot();
+        assert!(dot.contains(r#"[label="\\" shape=circle]"#));
+        assert!(dot.contains(r#"[label="\"" shape=circle]"#));
+        // Everything else is written as it is, where `{:?}` would escape it
+        assert!(dot.contains("[label=\"\u{2060}\" shape=circle]"));
     }
 }
@@ -3,7 +3,7 @@ use std::fs;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part2(text));
+    println!("{}", part2(text).unwrap());
 }
 
 #[cfg(test)]
@@ -13,6 +13,59 @@ mod tests {
     #[test]
     fn example2() {
         let text = fs::read_to_string("data/part2_example.txt").unwrap();
-        assert_eq!(part2(text), 467835)
+        assert_eq!(part2(text).unwrap(), 467835)
+    }
+
+    #[test]
+    fn rules() {
+        use day3::{Aggregation, Arity, Neighborhood, Rule, Symbols};
+
+        let text = fs::read_to_string("data/part2_example.txt").unwrap();
+        let schematic = day3::parse_schematic(&text).unwrap();
+        let gears = Rule::gears().matches(&schematic).unwrap();
+        assert_eq!(gears.len(), 2);
+        assert_eq!(gears[1].value, 755 * 598);
+
+        let largest = Rule {
+            symbols: Symbols::OneOf("*".to_string()),
+            arity: Arity::AtLeast(1),
+            neighborhood: Neighborhood::Eight,
+            aggregation: Aggregation::Max,
+        };
+        assert_eq!(largest.total(&schematic).unwrap(), 467 + 617 + 755);
+        let edges = Rule {
+            symbols: Symbols::Any,
+            neighborhood: Neighborhood::Four,
+            aggregation: Aggregation::Sum,
+            ..largest.clone()
+        };
+        assert_eq!(edges.total(&schematic).unwrap(), 35 + 633 + 617 + 664 + 598);
+        let alone = Rule {
+            arity: Arity::AtMost(0),
+            ..edges.clone()
+        };
+        let alone = alone.matches(&schematic).unwrap();
+        assert_eq!(alone.len(), 1);
+        assert_eq!(schematic.symbols[alone[0].symbol].symbol, '+');
+        assert!(Rule {
+            arity: Arity::AtMost(0),
+            ..Rule::part_numbers()
+        }
+        .matches(&schematic)
+        .unwrap()
+        .is_empty());
+
+        // Four of the largest part numbers around one symbol
+        let crowded = "4294967295.4294967295\n..........*..........\n4294967295.4294967295\n";
+        let schematic = day3::parse_schematic(crowded).unwrap();
+        let product = Rule {
+            arity: Arity::AtLeast(1),
+            ..Rule::gears()
+        };
+        assert_eq!(product.total(&schematic), Err(day3::Error::Overflow));
+        assert_eq!(
+            Rule::part_numbers().numbers_total(&schematic),
+            Ok(4 * 4294967295)
+        );
     }
 }
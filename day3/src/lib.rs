@@ -1,113 +1,505 @@
-use std::cmp::max;
+use std::collections::BTreeMap;
+use std::fmt;
 
+use parsing::{char_grid, parse_all, Located};
+use petgraph::graph::{NodeIndex, UnGraph};
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use serde_json::{json, Value};
+
+/// A number written across `len` cells of a row, starting at `col`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub col: usize,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// What's drawn in a cell, pointing into the schematic's numbers and symbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Number(usize),
+    Symbol(usize),
+}
+
+/// The part numbers and symbols of an engine schematic
+///
+/// Every cell is indexed, so finding what's next to a number or a symbol only
+/// looks at the cells around it.
 #[derive(Debug)]
-struct Coord(usize, usize);
-
-impl Coord {
-    /// Chebyshev distance between two coordinates
-    fn dist(&self, other: &Coord) -> usize {
-        max(self.0.abs_diff(other.0), self.1.abs_diff(other.1))
-    }
-
-    /// When self is the Coord for the start of a string and
-    /// length is the length of that string,
-    /// find the minimum Chebyshev distance between that string and the other Coord
-    fn min_dist(&self, other: &Coord, length: usize) -> usize {
-        let mut min = usize::MAX;
-        for n in 0..length {
-            let c = Coord(self.0, self.1 + n);
-            let d = c.dist(other);
-            if d < min {
-                min = d;
-            }
+pub struct Schematic {
+    pub part_nums: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
+    width: usize,
+    height: usize,
+    /// The cells in row major order
+    cells: Vec<Cell>,
+}
+
+impl Schematic {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> Cell {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col]
+        } else {
+            Cell::Empty
         }
-        min
+    }
+
+    /// The cells next to `len` cells of a row starting at `col`
+    fn around(
+        &self,
+        row: usize,
+        col: usize,
+        len: usize,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = Cell> + '_ {
+        let rows = row.saturating_sub(1)..=row + 1;
+        let cols = col.saturating_sub(1)..=col + len;
+        rows.flat_map(move |r| cols.clone().map(move |c| (r, c)))
+            .filter(move |&(r, c)| {
+                let in_span = col <= c && c < col + len;
+                match neighborhood {
+                    Neighborhood::Eight => r != row || !in_span,
+                    Neighborhood::Four => (r == row) != in_span,
+                }
+            })
+            .map(|(r, c)| self.cell(r, c))
+    }
+
+    /// The symbols next to a part number
+    pub fn symbols_next_to(&self, num: usize, neighborhood: Neighborhood) -> Vec<usize> {
+        let num = &self.part_nums[num];
+        self.around(num.row, num.col, num.len, neighborhood)
+            .filter_map(|cell| match cell {
+                Cell::Symbol(sym) => Some(sym),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The part numbers next to a symbol, each once even if it touches the
+    /// symbol in several places
+    pub fn numbers_next_to(&self, sym: usize, neighborhood: Neighborhood) -> Vec<usize> {
+        let sym = &self.symbols[sym];
+        let mut nums: Vec<usize> = self
+            .around(sym.row, sym.col, 1, neighborhood)
+            .filter_map(|cell| match cell {
+                Cell::Number(num) => Some(num),
+                _ => None,
+            })
+            .collect();
+        nums.sort_unstable();
+        nums.dedup();
+        nums
     }
 }
 
-#[derive(Debug)]
-struct Schematic {
-    part_nums: Vec<(String, Coord)>,
-    symbols: Vec<(char, Coord)>,
+/// Which cells count as next to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Sharing an edge
+    Four,
+    /// Sharing an edge or a corner
+    Eight,
+}
+
+/// Which symbols a rule looks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Symbols {
+    Any,
+    OneOf(String),
 }
 
-fn parse(text: String) -> Schematic {
+impl Symbols {
+    pub fn contains(&self, symbol: char) -> bool {
+        match self {
+            Symbols::Any => true,
+            Symbols::OneOf(symbols) => symbols.contains(symbol),
+        }
+    }
+}
+
+/// How many part numbers a symbol needs next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize),
+}
+
+impl Arity {
+    pub fn allows(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exactly(n) => count == n,
+            Arity::AtLeast(n) => count >= n,
+            Arity::AtMost(n) => count <= n,
+        }
+    }
+}
+
+/// How the part numbers next to a symbol combine into its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    /// `None` if the values multiply or add up to more than a `u64` holds
+    pub fn apply(&self, values: impl IntoIterator<Item = u32>) -> Option<u64> {
+        let mut values = values.into_iter().map(u64::from);
+        match self {
+            Aggregation::Product => values.try_fold(1u64, |acc, value| acc.checked_mul(value)),
+            Aggregation::Sum => values.try_fold(0u64, |acc, value| acc.checked_add(value)),
+            Aggregation::Max => Some(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+/// A symbol a rule picked out, with the part numbers next to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub symbol: usize,
+    pub numbers: Vec<usize>,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Parse(Located),
+    /// A symbol's value, or a rule's total, doesn't fit in a `u64`
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::Overflow => write!(f, "the total is too large for a u64"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Located> for Error {
+    fn from(err: Located) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// Picks out symbols by what they are and how many part numbers are next to
+/// them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub symbols: Symbols,
+    pub arity: Arity,
+    pub neighborhood: Neighborhood,
+    pub aggregation: Aggregation,
+}
+
+impl Rule {
+    /// Part 1: any symbol with a part number next to it
+    pub fn part_numbers() -> Self {
+        Self {
+            symbols: Symbols::Any,
+            arity: Arity::AtLeast(1),
+            neighborhood: Neighborhood::Eight,
+            aggregation: Aggregation::Sum,
+        }
+    }
+
+    /// Part 2: gears, `*` with exactly two part numbers next to it
+    pub fn gears() -> Self {
+        Self {
+            symbols: Symbols::OneOf("*".to_string()),
+            arity: Arity::Exactly(2),
+            neighborhood: Neighborhood::Eight,
+            aggregation: Aggregation::Product,
+        }
+    }
+
+    /// Every symbol the rule matches, in the order they're drawn
+    pub fn matches(&self, schematic: &Schematic) -> Result<Vec<Match>, Error> {
+        schematic
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, symbol)| self.symbols.contains(symbol.symbol))
+            .filter_map(|(sym, _)| {
+                let numbers = schematic.numbers_next_to(sym, self.neighborhood);
+                self.arity.allows(numbers.len()).then(|| {
+                    let value = self
+                        .aggregation
+                        .apply(numbers.iter().map(|&num| schematic.part_nums[num].value))
+                        .ok_or(Error::Overflow)?;
+                    Ok(Match {
+                        symbol: sym,
+                        value,
+                        numbers,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// The sum of the values of the matching symbols
+    pub fn total(&self, schematic: &Schematic) -> Result<u64, Error> {
+        self.matches(schematic)?
+            .iter()
+            .try_fold(0u64, |acc, m| acc.checked_add(m.value))
+            .ok_or(Error::Overflow)
+    }
+
+    /// The sum of the part numbers next to any matching symbol, counting each
+    /// number once
+    pub fn numbers_total(&self, schematic: &Schematic) -> Result<u64, Error> {
+        let mut numbers: Vec<usize> = self
+            .matches(schematic)?
+            .into_iter()
+            .flat_map(|m| m.numbers)
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers
+            .iter()
+            .try_fold(0u64, |acc, &num| {
+                acc.checked_add(u64::from(schematic.part_nums[num].value))
+            })
+            .ok_or(Error::Overflow)
+    }
+}
+
+pub fn parse_schematic(text: &str) -> Result<Schematic, Located> {
+    let grid = parse_all(text, char_grid(Some))?;
     let mut schematic = Schematic {
         part_nums: Vec::new(),
         symbols: Vec::new(),
+        width: grid.width,
+        height: grid.height,
+        cells: vec![Cell::Empty; grid.cells.len()],
     };
-    for (i, line) in text.lines().enumerate() {
-        let mut in_num = false;
-        let mut cur_num = String::new();
-        for (j, c) in line.chars().enumerate() {
-            match (in_num, c) {
-                (true, c) if c.is_numeric() => cur_num.push(c),
-                (true, c) => {
-                    schematic
-                        .part_nums
-                        .push((cur_num.clone(), Coord(i, j - cur_num.len())));
-                    in_num = false;
-                    cur_num = String::new();
-                    if c != '.' {
-                        schematic.symbols.push((c, Coord(i, j)));
-                    }
-                }
-                (false, c) if c.is_numeric() => {
-                    in_num = true;
-                    cur_num.push(c);
-                }
-                (false, c) => {
-                    if c != '.' {
-                        schematic.symbols.push((c, Coord(i, j)));
-                    }
+    for (i, line) in grid.into_rows().into_iter().enumerate() {
+        let mut j = 0;
+        while j < line.len() {
+            let idx = i * schematic.width + j;
+            let c = line[j];
+            if let Some(digit) = c.to_digit(10) {
+                let mut num = PartNumber {
+                    value: digit,
+                    row: i,
+                    col: j,
+                    len: 1,
+                };
+                while let Some(digit) = line.get(j + num.len).and_then(|c| c.to_digit(10)) {
+                    num.value = num
+                        .value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit))
+                        .ok_or_else(|| Located {
+                            line: i + 1,
+                            column: j + 1,
+                            message: "part number is too large".to_string(),
+                        })?;
+                    num.len += 1;
                 }
+                let cell = Cell::Number(schematic.part_nums.len());
+                schematic.cells[idx..idx + num.len].fill(cell);
+                schematic.part_nums.push(num);
+                j += num.len;
+                continue;
+            }
+            if c != '.' {
+                schematic.cells[idx] = Cell::Symbol(schematic.symbols.len());
+                schematic.symbols.push(Symbol {
+                    symbol: c,
+                    row: i,
+                    col: j,
+                });
             }
+            j += 1;
         }
-        if in_num {
-            schematic
-                .part_nums
-                .push((cur_num.clone(), Coord(i, line.len() - cur_num.len())))
+    }
+    Ok(schematic)
+}
+
+/// A part number or a symbol in a schematic's graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    Number(PartNumber),
+    Symbol(Symbol),
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Number(num) => write!(f, "{}", num.value),
+            Node::Symbol(sym) => write!(f, "{}", sym.symbol),
         }
     }
-    schematic
 }
 
-pub fn part1(text: String) -> u32 {
-    let schematic = parse(text);
-    schematic
-        .part_nums
-        .iter()
-        .filter_map(|(num, start)| {
-            for sym in schematic.symbols.iter() {
-                if start.min_dist(&sym.1, num.len()) == 1 {
-                    return Some(num.parse::<u32>().unwrap());
-                }
-            }
-            None
-        })
-        .sum()
+/// The part numbers and symbols of a schematic, joined where they're next to
+/// each other
+///
+/// The numbers come first, in the same order as in the schematic, followed
+/// by the symbols.
+pub struct PartGraph {
+    pub graph: UnGraph<Node, ()>,
 }
 
-pub fn part2(text: String) -> u32 {
-    let schematic = parse(text);
-    schematic
-        .symbols
-        .iter()
-        .filter_map(|(sym, coord)| {
-            if sym != &'*' {
-                return None;
-            }
-            let mut adj_nums = Vec::new();
-            for num in schematic.part_nums.iter() {
-                if num.1.min_dist(coord, num.0.len()) == 1 {
-                    adj_nums.push(num.0.parse::<u32>().unwrap());
-                }
-            }
-            if adj_nums.len() == 2 {
-                Some(adj_nums[0] * adj_nums[1])
-            } else {
-                None
+impl PartGraph {
+    pub fn new(schematic: &Schematic, neighborhood: Neighborhood) -> Self {
+        let mut graph = UnGraph::new_undirected();
+        for &num in &schematic.part_nums {
+            graph.add_node(Node::Number(num));
+        }
+        for &sym in &schematic.symbols {
+            graph.add_node(Node::Symbol(sym));
+        }
+        let symbol = |sym: usize| NodeIndex::new(schematic.part_nums.len() + sym);
+        for num in 0..schematic.part_nums.len() {
+            for sym in schematic.symbols_next_to(num, neighborhood) {
+                graph.add_edge(NodeIndex::new(num), symbol(sym), ());
             }
+        }
+        Self { graph }
+    }
+
+    /// The groups of nodes linked to each other, ordered by their first node
+    pub fn components(&self) -> Vec<Vec<NodeIndex>> {
+        let mut sets = UnionFind::new(self.graph.node_count());
+        for edge in self.graph.edge_references() {
+            sets.union(edge.source().index(), edge.target().index());
+        }
+        let mut components: BTreeMap<usize, Vec<NodeIndex>> = BTreeMap::new();
+        for (node, set) in sets.into_labeling().into_iter().enumerate() {
+            components
+                .entry(set)
+                .or_default()
+                .push(NodeIndex::new(node));
+        }
+        let mut components: Vec<_> = components.into_values().collect();
+        components.sort_unstable();
+        components
+    }
+
+    fn unlinked(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.graph
+            .node_indices()
+            .filter(|&node| self.graph.neighbors(node).next().is_none())
+    }
+
+    /// Numbers with no symbol next to them
+    pub fn orphan_numbers(&self) -> Vec<NodeIndex> {
+        self.unlinked()
+            .filter(|&node| matches!(self.graph[node], Node::Number(_)))
+            .collect()
+    }
+
+    /// Symbols with no number next to them
+    pub fn lonely_symbols(&self) -> Vec<NodeIndex> {
+        self.unlinked()
+            .filter(|&node| matches!(self.graph[node], Node::Symbol(_)))
+            .collect()
+    }
+
+    /// The graph in Graphviz's DOT language, with numbers drawn as boxes and
+    /// symbols as circles
+    pub fn dot(&self) -> String {
+        let mut dot = String::from("graph schematic {\n");
+        for node in self.graph.node_indices() {
+            let shape = match self.graph[node] {
+                Node::Number(_) => "box",
+                Node::Symbol(_) => "circle",
+            };
+            let label = self.graph[node]
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            dot += &format!(
+                "    {} [label=\"{}\" shape={}]\n",
+                node.index(),
+                label,
+                shape
+            );
+        }
+        for edge in self.graph.edge_references() {
+            dot += &format!(
+                "    {} -- {}\n",
+                edge.source().index(),
+                edge.target().index()
+            );
+        }
+        dot + "}\n"
+    }
+
+    /// The nodes and edges along with the components, orphan numbers and
+    /// lonely symbols, with nodes referred to by their index
+    pub fn json(&self) -> Value {
+        let ids = |nodes: Vec<NodeIndex>| -> Vec<usize> {
+            nodes.into_iter().map(|node| node.index()).collect()
+        };
+        let nodes: Vec<Value> = self
+            .graph
+            .node_indices()
+            .map(|node| match self.graph[node] {
+                Node::Number(num) => json!({
+                    "id": node.index(),
+                    "kind": "number",
+                    "value": num.value,
+                    "row": num.row,
+                    "col": num.col,
+                    "len": num.len,
+                }),
+                Node::Symbol(sym) => json!({
+                    "id": node.index(),
+                    "kind": "symbol",
+                    "symbol": sym.symbol.to_string(),
+                    "row": sym.row,
+                    "col": sym.col,
+                }),
+            })
+            .collect();
+        let edges: Vec<[usize; 2]> = self
+            .graph
+            .edge_references()
+            .map(|edge| [edge.source().index(), edge.target().index()])
+            .collect();
+        json!({
+            "nodes": nodes,
+            "edges": edges,
+            "components": self.components().into_iter().map(ids).collect::<Vec<_>>(),
+            "orphan_numbers": ids(self.orphan_numbers()),
+            "lonely_symbols": ids(self.lonely_symbols()),
         })
-        .sum()
+    }
+}
+
+pub fn part1(text: String) -> Result<u64, Error> {
+    let schematic = parse_schematic(&text)?;
+    Rule::part_numbers().numbers_total(&schematic)
+}
+
+pub fn part2(text: String) -> Result<u64, Error> {
+    let schematic = parse_schematic(&text)?;
+    Rule::gears().total(&schematic)
 }
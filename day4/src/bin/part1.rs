@@ -3,7 +3,7 @@ use std::fs;
 
 fn main() {
     let text = fs::read_to_string("data/input.txt").unwrap();
-    println!("{}", part1(text));
+    println!("{}", part1(text).unwrap());
 }
 
 #[cfg(test)]
@@ -13,6 +13,6 @@ mod tests {
     #[test]
     fn example1() {
         let text = fs::read_to_string("data/part1_example.txt").unwrap();
-        assert_eq!(part1(text), 13)
+        assert_eq!(part1(text).unwrap(), 13)
     }
 }
@@ -1,10 +1,10 @@
 use itertools::Itertools;
-use nom::bytes::complete::tag;
 use nom::character::complete::*;
 use nom::combinator::*;
+use nom::error::context;
 use nom::multi::*;
 use nom::sequence::*;
-use nom::IResult;
+use parsing::{label, numbers, parse_all, IResult};
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
 struct Range {
@@ -124,45 +124,45 @@ struct Almanac {
     maps: Vec<Map>,
 }
 
-fn parse_rule(input: &str) -> IResult<&str, Rule> {
-    map(separated_list1(space1, digit1), |v: Vec<&str>| Rule {
-        dst: v[0].parse().unwrap(),
-        range: Range {
-            src: v[1].parse().unwrap(),
-            len: v[2].parse().unwrap(),
-        },
-    })(input)
+fn parse_rule(input: &str) -> IResult<'_, Rule> {
+    context(
+        "destination, source and length",
+        map_opt(numbers, |v: Vec<u64>| match v[..] {
+            [dst, src, len] => Some(Rule {
+                dst,
+                range: Range { src, len },
+            }),
+            _ => None,
+        }),
+    )(input)
 }
 
-fn parse_map(input: &str) -> IResult<&str, Map> {
-    let (input, _) = terminated(
-        separated_list1(char('-'), alpha1),
-        tuple((space1, tag("map:"), multispace0)),
-    )(input)?;
-    let (input, map) = fold_many1(
-        terminated(parse_rule, multispace0),
-        || Map { rules: Vec::new() },
-        |mut acc, r| {
-            acc.rules.push(r);
-            acc
-        },
-    )(input)?;
-    Ok((input, map))
+fn parse_map(input: &str) -> IResult<'_, Map> {
+    map(
+        preceded(
+            pair(label, line_ending),
+            separated_list1(line_ending, parse_rule),
+        ),
+        |rules| Map { rules },
+    )(input)
+}
+
+fn parse_maps(input: &str) -> IResult<'_, Vec<Map>> {
+    preceded(
+        pair(line_ending, line_ending),
+        separated_list1(pair(line_ending, line_ending), parse_map),
+    )(input)
 }
 
-fn parse_almanac(input: &str) -> IResult<&str, Almanac> {
+fn parse_almanac(input: &str) -> IResult<'_, Almanac> {
     let (input, seeds) = preceded(
-        pair(tag("seeds:"), space1),
-        separated_list1(
-            space1,
-            map(digit1, |s: &str| Range {
-                src: s.parse::<u64>().unwrap(),
-                len: 1,
-            }),
-        ),
+        label,
+        map(numbers, |seeds: Vec<u64>| {
+            seeds.into_iter().map(|src| Range { src, len: 1 }).collect()
+        }),
     )(input)?;
-    let (_, maps) = preceded(multispace0, many1(parse_map))(input)?;
-    Ok(("", Almanac { seeds, maps }))
+    let (input, maps) = parse_maps(input)?;
+    Ok((input, Almanac { seeds, maps }))
 }
 
 fn find_min_seed(almanac: Almanac) -> u64 {
@@ -179,29 +179,33 @@ fn find_min_seed(almanac: Almanac) -> u64 {
 }
 
 pub fn part1(text: String) -> u64 {
-    let (_, almanac) = parse_almanac(text.as_str()).unwrap();
+    let almanac = parse_all(text.as_str(), parse_almanac).unwrap();
     find_min_seed(almanac)
 }
 
-fn parse_almanac2(input: &str) -> IResult<&str, Almanac> {
+fn parse_almanac2(input: &str) -> IResult<'_, Almanac> {
     let (input, seeds) = preceded(
-        pair(tag("seeds:"), space1),
-        separated_list1(
-            space1,
-            map(
-                separated_pair(digit1, space1, digit1),
-                |(s1, s2): (&str, &str)| Range {
-                    src: s1.parse::<u64>().unwrap(),
-                    len: s2.parse::<u64>().unwrap(),
-                },
-            ),
+        label,
+        context(
+            "pairs of seed numbers",
+            map_opt(numbers, |seeds: Vec<u64>| {
+                seeds.len().is_multiple_of(2).then(|| {
+                    seeds
+                        .chunks(2)
+                        .map(|pair| Range {
+                            src: pair[0],
+                            len: pair[1],
+                        })
+                        .collect()
+                })
+            }),
         ),
     )(input)?;
-    let (_, maps) = preceded(multispace0, many1(parse_map))(input)?;
-    Ok(("", Almanac { seeds, maps }))
+    let (input, maps) = parse_maps(input)?;
+    Ok((input, Almanac { seeds, maps }))
 }
 
 pub fn part2(text: String) -> u64 {
-    let (_, almanac) = parse_almanac2(text.as_str()).unwrap();
+    let almanac = parse_all(text.as_str(), parse_almanac2).unwrap();
     find_min_seed(almanac)
 }
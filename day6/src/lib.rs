@@ -1,24 +1,18 @@
 use std::iter::zip;
 
 use nom::{
-    bytes::complete::take_until,
-    character::complete::{anychar, digit1, space1},
-    combinator::map,
+    character::complete::{digit1, line_ending, space1},
     multi::separated_list1,
-    sequence::{preceded, tuple},
-    IResult,
+    sequence::{preceded, separated_pair},
 };
+use parsing::{label, numbers, parse_all, IResult};
 
-fn parse(text: &str) -> IResult<&str, (Vec<u32>, Vec<u32>)> {
-    let (text, times) = preceded(
-        tuple((take_until(":"), anychar, space1)),
-        separated_list1(space1, map(digit1, |s: &str| s.parse::<u32>().unwrap())),
-    )(text)?;
-    let (text, distances) = preceded(
-        tuple((take_until(":"), anychar, space1)),
-        separated_list1(space1, map(digit1, |s: &str| s.parse::<u32>().unwrap())),
-    )(text)?;
-    Ok((text, (times, distances)))
+fn parse(text: &str) -> IResult<'_, (Vec<u32>, Vec<u32>)> {
+    separated_pair(
+        preceded(label, numbers),
+        line_ending,
+        preceded(label, numbers),
+    )(text)
 }
 
 /// Boat distance if button held for button_secs and race is total_secs
@@ -27,7 +21,7 @@ fn dist(button_secs: u32, total_secs: u32) -> u32 {
 }
 
 pub fn part1(text: String) -> u32 {
-    let (_, (times, distances)) = parse(text.as_str()).unwrap();
+    let (times, distances) = parse_all(text.as_str(), parse).unwrap();
     zip(times, distances)
         .map(|(time, distance)| {
             let mut min = 0;
@@ -43,14 +37,12 @@ pub fn part1(text: String) -> u32 {
         .product()
 }
 
-fn parse2(text: &str) -> IResult<&str, (u64, u64)> {
-    let (text, times) = preceded(
-        tuple((take_until(":"), anychar, space1)),
-        separated_list1(space1, digit1),
-    )(text)?;
-    let (text, distances) = preceded(
-        tuple((take_until(":"), anychar, space1)),
-        separated_list1(space1, digit1),
+/// Same as `parse` but the spaces between the digits don't count
+fn parse2(text: &str) -> IResult<'_, (u64, u64)> {
+    let (text, (times, distances)) = separated_pair(
+        preceded(label, separated_list1(space1, digit1)),
+        line_ending,
+        preceded(label, separated_list1(space1, digit1)),
     )(text)?;
     Ok((
         text,
@@ -67,7 +59,7 @@ fn dist2(button_secs: u64, total_secs: u64) -> u64 {
 }
 
 pub fn part2(text: String) -> u64 {
-    let (_, (time, distance)) = parse2(text.as_str()).unwrap();
+    let (time, distance) = parse_all(text.as_str(), parse2).unwrap();
     let min;
     let mut lower = 0;
     let mut upper = time / 2;
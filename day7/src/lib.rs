@@ -2,12 +2,10 @@ use std::cmp::Ordering;
 use std::{collections::HashMap, iter::zip};
 
 use nom::{
-    character::complete::{alphanumeric1, digit1, multispace1, space1},
-    combinator::map,
-    multi::separated_list1,
+    bytes::complete::take_while1, character::complete::space1, combinator::map,
     sequence::separated_pair,
-    IResult,
 };
+use parsing::{number, parse_all, solve_lines, IResult, Located};
 
 #[derive(PartialOrd, Ord, PartialEq, Eq)]
 enum HandType {
@@ -68,21 +66,19 @@ fn hand_type_joker(hand: &str) -> HandType {
     }
 }
 
-fn players(text: &str) -> IResult<&str, Vec<Player>> {
-    separated_list1(
-        multispace1,
-        map(
-            separated_pair(alphanumeric1, space1, digit1),
-            |(hand, bid)| Player {
-                hand,
-                bid: bid.parse().unwrap(),
-            },
-        ),
+fn player(text: &str) -> IResult<'_, Player<'_>> {
+    map(
+        separated_pair(take_while1(|c| "AKQJT98765432".contains(c)), space1, number),
+        |(hand, bid)| Player { hand, bid },
     )(text)
 }
 
-pub fn part1(text: String) -> u32 {
-    let (_, mut players) = players(text.as_str()).unwrap();
+fn players(text: &str) -> Result<Vec<Player<'_>>, Located> {
+    solve_lines(text, |line| parse_all(line, player))
+}
+
+pub fn part1(text: String) -> Result<u32, Located> {
+    let mut players = players(&text)?;
     players.sort_by(|p1, p2| {
         if hand_type(p1.hand) != hand_type(p2.hand) {
             return hand_type(p1.hand).cmp(&hand_type(p2.hand));
@@ -120,15 +116,15 @@ pub fn part1(text: String) -> u32 {
         }
         Ordering::Equal
     });
-    players
+    Ok(players
         .iter()
         .enumerate()
         .map(|(idx, p)| (idx as u32 + 1) * p.bid)
-        .sum()
+        .sum())
 }
 
-pub fn part2(text: String) -> u32 {
-    let (_, mut players) = players(text.as_str()).unwrap();
+pub fn part2(text: String) -> Result<u32, Located> {
+    let mut players = players(&text)?;
     players.sort_by(|p1, p2| {
         if hand_type_joker(p1.hand) != hand_type_joker(p2.hand) {
             return hand_type_joker(p1.hand).cmp(&hand_type_joker(p2.hand));
@@ -166,9 +162,9 @@ pub fn part2(text: String) -> u32 {
         }
         Ordering::Equal
     });
-    players
+    Ok(players
         .iter()
         .enumerate()
         .map(|(idx, p)| (idx as u32 + 1) * p.bid)
-        .sum()
+        .sum())
 }
@@ -2,14 +2,13 @@ use std::collections::HashMap;
 
 use nom::{
     bytes::complete::tag,
-    character::complete::{alpha1, alphanumeric1, multispace1},
+    character::complete::{alphanumeric1, line_ending, multispace1, one_of},
     combinator::map,
-    error::Error,
-    multi::fold_many1,
-    sequence::{delimited, separated_pair, terminated},
-    IResult,
+    multi::{many1, separated_list1},
+    sequence::{delimited, separated_pair},
 };
 use num::integer::lcm;
+use parsing::{parse_all, IResult};
 
 #[derive(Debug)]
 struct Documents {
@@ -17,40 +16,44 @@ struct Documents {
     paths: HashMap<String, (String, String)>,
 }
 
-fn paths(text: &str) -> IResult<&str, HashMap<String, (String, String)>> {
-    fold_many1(
-        terminated(
-            separated_pair(
-                alphanumeric1::<&str, Error<&str>>,
-                tag(" = "),
-                delimited(
-                    tag("("),
-                    separated_pair(alphanumeric1, tag(", "), alphanumeric1),
-                    tag(")"),
-                ),
+/// `AAA = (BBB, CCC)`
+fn path(text: &str) -> IResult<'_, (String, (String, String))> {
+    map(
+        separated_pair(
+            alphanumeric1,
+            tag(" = "),
+            delimited(
+                tag("("),
+                separated_pair(alphanumeric1, tag(", "), alphanumeric1),
+                tag(")"),
             ),
-            multispace1,
         ),
-        HashMap::new,
-        |mut acc, (loc, (left, right))| {
-            acc.insert(loc.to_string(), (left.to_string(), right.to_string()));
-            acc
+        |(loc, (left, right)): (&str, (&str, &str))| {
+            (loc.to_string(), (left.to_string(), right.to_string()))
         },
     )(text)
 }
 
-fn documents(text: &str) -> IResult<&str, Documents> {
+fn documents(text: &str) -> IResult<'_, Documents> {
     map(
-        separated_pair(alpha1, multispace1, paths),
+        separated_pair(
+            many1(one_of("LR")),
+            multispace1,
+            separated_list1(line_ending, path),
+        ),
         |(directions, paths)| Documents {
-            directions: directions.chars().collect(),
-            paths,
+            directions,
+            paths: paths.into_iter().collect(),
         },
     )(text)
 }
 
+fn parse_documents(text: &str) -> Documents {
+    parse_all(text, documents).expect("Failed to parse.")
+}
+
 pub fn part1(text: String) -> usize {
-    let (_, documents) = documents(text.as_str()).unwrap();
+    let documents = parse_documents(&text);
     let mut cur = "AAA";
     let mut steps = 0;
     while cur != "ZZZ" {
@@ -69,7 +72,7 @@ pub fn part1(text: String) -> usize {
 }
 
 pub fn part2(text: String) -> usize {
-    let (_, documents) = documents(text.as_str()).unwrap();
+    let documents = parse_documents(&text);
     let mut curs = documents
         .paths
         .keys()
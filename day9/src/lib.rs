@@ -1,11 +1,9 @@
+use parsing::{numbers, parse_all, solve_lines};
+
 pub fn part1(text: String) -> i64 {
-    let mut sum = 0;
-    for line in text.lines() {
+    solve_lines(&text, |line| {
         let mut finals = Vec::new();
-        let mut cur_seq = line
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect::<Vec<i64>>();
+        let mut cur_seq = parse_all(line, numbers::<i64>)?;
         while !cur_seq.iter().all(|n| *n == 0) {
             let mut next_seq = Vec::new();
             for idx in 0..(cur_seq.len() - 1) {
@@ -14,19 +12,17 @@ pub fn part1(text: String) -> i64 {
             finals.push(cur_seq[cur_seq.len()-1]);
             cur_seq = next_seq;
         }
-        sum += finals.iter().sum::<i64>();
-    }
-    sum
+        Ok(finals.iter().sum::<i64>())
+    })
+    .unwrap()
+    .iter()
+    .sum()
 }
 
 pub fn part2(text: String) -> i64 {
-    let mut sum = 0;
-    for line in text.lines() {
+    solve_lines(&text, |line| {
         let mut firsts = Vec::new();
-        let mut cur_seq = line
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect::<Vec<i64>>();
+        let mut cur_seq = parse_all(line, numbers::<i64>)?;
         while !cur_seq.iter().all(|n| *n == 0) {
             let mut next_seq = Vec::new();
             for idx in 0..(cur_seq.len() - 1) {
@@ -35,7 +31,9 @@ pub fn part2(text: String) -> i64 {
             firsts.push(cur_seq[0]);
             cur_seq = next_seq;
         }
-        sum += firsts.iter().rev().fold(0, |acc, n| n - acc);
-    }
-    sum
+        Ok(firsts.iter().rev().fold(0, |acc, n| n - acc))
+    })
+    .unwrap()
+    .iter()
+    .sum()
 }
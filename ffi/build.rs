@@ -0,0 +1,15 @@
+use std::env;
+
+/// Generates the C header into `OUT_DIR`
+///
+/// The copy in `include/aoc.h` is checked against it by the `header` test, run
+/// that with `AOC_UPDATE_HEADER=1` to refresh it after changing the API.
+fn main() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&crate_dir)
+        .expect("Failed to generate the C header")
+        .write_to_file(format!("{}/aoc.h", out_dir));
+}
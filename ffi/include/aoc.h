@@ -0,0 +1,85 @@
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result codes returned by every function in the C API
+ */
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  AOC_STATUS_UNKNOWN_SOLVER = 1,
+  AOC_STATUS_INVALID_INPUT = 2,
+  AOC_STATUS_NULL_POINTER = 3,
+  AOC_STATUS_BUFFER_TOO_SMALL = 4,
+  AOC_STATUS_SOLVER_PANICKED = 5,
+  /**
+   * The solver was stopped by its timeout before it finished
+   */
+  AOC_STATUS_INTERRUPTED = 6,
+} AocStatus;
+
+/**
+ * A caller owned buffer that receives a NUL terminated string
+ *
+ * `len` is set to the length of the string without its terminator, even when
+ * `capacity` was too small to hold it, so the caller can retry with a larger buffer.
+ */
+typedef struct AocBuffer {
+  char *data;
+  size_t capacity;
+  size_t len;
+} AocBuffer;
+
+/**
+ * Solves one part of a day's puzzle with its default parameters
+ *
+ * `input_ptr` must point to `len` bytes of UTF-8 puzzle input. On success the
+ * answer is written to `out_buf` in decimal and `AOC_STATUS_OK` is returned.
+ * On any other status `out_buf` holds a description of the error if it fits.
+ *
+ * # Safety
+ *
+ * `input_ptr` must be valid for reads of `len` bytes, `out_buf` must point to
+ * an `AocBuffer` whose `data` is valid for writes of `capacity` bytes.
+ */
+enum AocStatus aoc_solve(uint32_t day,
+                         uint32_t part,
+                         const uint8_t *input_ptr,
+                         size_t len,
+                         struct AocBuffer *out_buf);
+
+/**
+ * Like `aoc_solve`, but gives up after `timeout_ms` milliseconds
+ *
+ * Solvers that check for timeouts return `AOC_STATUS_INTERRUPTED` when they
+ * run out of time, the rest run to completion.
+ *
+ * # Safety
+ *
+ * The same as for `aoc_solve`.
+ */
+enum AocStatus aoc_solve_with_timeout(uint32_t day,
+                                      uint32_t part,
+                                      const uint8_t *input_ptr,
+                                      size_t len,
+                                      uint64_t timeout_ms,
+                                      struct AocBuffer *out_buf);
+
+/**
+ * Returns 1 if a solver is registered for the given day and part, 0 otherwise
+ */
+int32_t aoc_has_solver(uint32_t day, uint32_t part);
+
+/**
+ * Returns a static, NUL terminated description of a status code
+ */
+const char *aoc_status_message(int32_t status);
+
+#endif  /* AOC_H */
@@ -0,0 +1,250 @@
+use std::ffi::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+use std::{ptr, slice, str};
+
+use common::context::SolverContext;
+use solvers::{Error, Params};
+
+/// Result codes returned by every function in the C API
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    UnknownSolver = 1,
+    InvalidInput = 2,
+    NullPointer = 3,
+    BufferTooSmall = 4,
+    SolverPanicked = 5,
+    /// The solver was stopped by its timeout before it finished
+    Interrupted = 6,
+}
+
+/// A caller owned buffer that receives a NUL terminated string
+///
+/// `len` is set to the length of the string without its terminator, even when
+/// `capacity` was too small to hold it, so the caller can retry with a larger buffer.
+#[repr(C)]
+pub struct AocBuffer {
+    pub data: *mut c_char,
+    pub capacity: usize,
+    pub len: usize,
+}
+
+impl AocBuffer {
+    fn write(&mut self, text: &str) -> AocStatus {
+        self.len = text.len();
+        if self.data.is_null() || self.capacity <= text.len() {
+            return AocStatus::BufferTooSmall;
+        }
+        // SAFETY: the caller guarantees `data` points to `capacity` writable bytes
+        unsafe {
+            ptr::copy_nonoverlapping(text.as_ptr(), self.data as *mut u8, text.len());
+            *self.data.add(text.len()) = 0;
+        }
+        AocStatus::Ok
+    }
+}
+
+fn solve(day: u32, part: u32, input: &[u8], ctx: &SolverContext, out: &mut AocBuffer) -> AocStatus {
+    let Ok(text) = str::from_utf8(input) else {
+        out.write("input is not valid UTF-8");
+        return AocStatus::InvalidInput;
+    };
+    match solvers::solve_with_context(day, part, text, &Params::new(), ctx) {
+        Ok(answer) => out.write(&answer.to_string()),
+        Err(err) => {
+            out.write(&err.to_string());
+            match err {
+                Error::UnknownSolver { .. } | Error::UnknownVariant { .. } => {
+                    AocStatus::UnknownSolver
+                }
+                Error::BadParam(_) | Error::BadInput(_) => AocStatus::InvalidInput,
+                Error::Interrupted(_) => AocStatus::Interrupted,
+                // The C API never checkpoints, so a checkpoint failure is a bug
+                Error::Checkpoint(_) | Error::Panicked(_) => AocStatus::SolverPanicked,
+            }
+        }
+    }
+}
+
+/// Reads the input and runs `solve` on it, catching any panic
+unsafe fn solve_raw(
+    day: u32,
+    part: u32,
+    input_ptr: *const u8,
+    len: usize,
+    ctx: &SolverContext,
+    out_buf: *mut AocBuffer,
+) -> AocStatus {
+    if out_buf.is_null() {
+        return AocStatus::NullPointer;
+    }
+    let out = &mut *out_buf;
+    if input_ptr.is_null() && len != 0 {
+        out.write("input pointer is null");
+        return AocStatus::NullPointer;
+    }
+    let input = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input_ptr, len)
+    };
+    panic::catch_unwind(AssertUnwindSafe(|| solve(day, part, input, ctx, out)))
+        .unwrap_or(AocStatus::SolverPanicked)
+}
+
+/// Solves one part of a day's puzzle with its default parameters
+///
+/// `input_ptr` must point to `len` bytes of UTF-8 puzzle input. On success the
+/// answer is written to `out_buf` in decimal and `AOC_STATUS_OK` is returned.
+/// On any other status `out_buf` holds a description of the error if it fits.
+///
+/// # Safety
+///
+/// `input_ptr` must be valid for reads of `len` bytes, `out_buf` must point to
+/// an `AocBuffer` whose `data` is valid for writes of `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u32,
+    part: u32,
+    input_ptr: *const u8,
+    len: usize,
+    out_buf: *mut AocBuffer,
+) -> AocStatus {
+    solve_raw(day, part, input_ptr, len, &SolverContext::new(), out_buf)
+}
+
+/// Like `aoc_solve`, but gives up after `timeout_ms` milliseconds
+///
+/// Solvers that check for timeouts return `AOC_STATUS_INTERRUPTED` when they
+/// run out of time, the rest run to completion.
+///
+/// # Safety
+///
+/// The same as for `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve_with_timeout(
+    day: u32,
+    part: u32,
+    input_ptr: *const u8,
+    len: usize,
+    timeout_ms: u64,
+    out_buf: *mut AocBuffer,
+) -> AocStatus {
+    let ctx = SolverContext::new().with_timeout(Duration::from_millis(timeout_ms));
+    solve_raw(day, part, input_ptr, len, &ctx, out_buf)
+}
+
+/// Returns 1 if a solver is registered for the given day and part, 0 otherwise
+#[no_mangle]
+pub extern "C" fn aoc_has_solver(day: u32, part: u32) -> i32 {
+    solvers::find(day, part).is_some() as i32
+}
+
+/// Returns a static, NUL terminated description of a status code
+#[no_mangle]
+pub extern "C" fn aoc_status_message(status: i32) -> *const c_char {
+    let message: &'static [u8] = match status {
+        0 => b"ok\0",
+        1 => b"no solver registered for that day and part\0",
+        2 => b"invalid input\0",
+        3 => b"null pointer argument\0",
+        4 => b"output buffer too small\0",
+        5 => b"solver panicked\0",
+        6 => b"solver interrupted by its timeout\0",
+        _ => b"unknown status\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::{env, fs};
+
+    fn call(day: u32, part: u32, input: &[u8], capacity: usize) -> (AocStatus, String, usize) {
+        let mut data = vec![0 as c_char; capacity];
+        let mut buf = AocBuffer {
+            data: data.as_mut_ptr(),
+            capacity,
+            len: 0,
+        };
+        let status = unsafe { aoc_solve(day, part, input.as_ptr(), input.len(), &mut buf) };
+        let text = if status != AocStatus::BufferTooSmall && capacity > 0 {
+            unsafe { CStr::from_ptr(data.as_ptr()) }
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            String::new()
+        };
+        (status, text, buf.len)
+    }
+
+    #[test]
+    fn example() {
+        let text = fs::read_to_string("../day1/data/part2_example.txt").unwrap();
+        assert_eq!(
+            call(1, 2, text.as_bytes(), 32),
+            (AocStatus::Ok, "281".to_string(), 3)
+        )
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let text = fs::read_to_string("../day1/data/part2_example.txt").unwrap();
+        assert_eq!(
+            call(1, 2, text.as_bytes(), 3),
+            (AocStatus::BufferTooSmall, String::new(), 3)
+        )
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(call(26, 1, b"", 64).0, AocStatus::UnknownSolver);
+        assert_eq!(call(1, 1, &[0xff, 0xfe], 64).0, AocStatus::InvalidInput);
+        assert_eq!(call(1, 1, b"1\nabc\n", 64).0, AocStatus::InvalidInput);
+        assert_eq!(call(24, 2, b"", 64).0, AocStatus::SolverPanicked);
+        let status = unsafe { aoc_solve(1, 1, ptr::null(), 0, ptr::null_mut()) };
+        assert_eq!(status, AocStatus::NullPointer);
+    }
+
+    #[test]
+    fn timeout() {
+        let text = fs::read_to_string("../day23/data/input.txt").unwrap();
+        let mut data = vec![0 as c_char; 128];
+        let mut buf = AocBuffer {
+            data: data.as_mut_ptr(),
+            capacity: data.len(),
+            len: 0,
+        };
+        let status =
+            unsafe { aoc_solve_with_timeout(23, 2, text.as_ptr(), text.len(), 50, &mut buf) };
+        assert_eq!(status, AocStatus::Interrupted);
+        let message = unsafe { CStr::from_ptr(aoc_status_message(status as i32)) };
+        assert_eq!(message.to_str(), Ok("solver interrupted by its timeout"));
+    }
+
+    #[test]
+    fn status_messages() {
+        let message =
+            unsafe { CStr::from_ptr(aoc_status_message(AocStatus::BufferTooSmall as i32)) };
+        assert_eq!(message.to_str(), Ok("output buffer too small"));
+        assert_eq!(aoc_has_solver(25, 2), 1);
+        assert_eq!(aoc_has_solver(25, 3), 0);
+    }
+
+    #[test]
+    fn header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/aoc.h"));
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/aoc.h");
+        if env::var_os("AOC_UPDATE_HEADER").is_some() {
+            fs::write(path, generated).unwrap();
+        }
+        assert!(
+            fs::read_to_string(path).unwrap() == generated,
+            "include/aoc.h is out of date, run the tests with AOC_UPDATE_HEADER=1 to update it"
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use std::fmt;
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    bytes::complete::take_till1,
+    character::complete::{
+        char, digit1, line_ending, multispace0, not_line_ending, space0, space1,
+    },
+    combinator::{eof, map_res, opt, recognize},
+    error::{context, ContextError, ErrorKind, FromExternalError},
+    multi::separated_list1,
+    sequence::{pair, terminated, tuple},
+    Finish, Parser,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Where a parser gave up, and what it was looking for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<'a> {
+    /// The input that was left when the parser failed
+    pub input: &'a str,
+    pub kind: ErrorKind,
+    pub context: Option<&'static str>,
+}
+
+impl<'a> nom::error::ParseError<&'a str> for Error<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Error {
+            input,
+            kind,
+            context: None,
+        }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> ContextError<&'a str> for Error<'a> {
+    /// Keeps the innermost context since that's the most specific
+    fn add_context(_input: &'a str, ctx: &'static str, mut other: Self) -> Self {
+        other.context.get_or_insert(ctx);
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for Error<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _e: E) -> Self {
+        Error {
+            input,
+            kind,
+            context: None,
+        }
+    }
+}
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T, Error<'a>>;
+
+/// A parse error with its 1-based line and column in the original input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Located {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Located {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for Located {}
+
+/// Works out where in `text` the error happened, `err` must come from parsing `text`
+pub fn locate(text: &str, err: &Error) -> Located {
+    let offset = err.input.as_ptr() as usize - text.as_ptr() as usize;
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let found = match err.input.chars().next() {
+        Some('\n') | Some('\r') => "end of line".to_string(),
+        Some(c) => format!("{:?}", c),
+        None => "end of input".to_string(),
+    };
+    let message = match err.context {
+        Some(context) => format!("expected {}, found {}", context, found),
+        None => format!("{} failed at {}", err.kind.description(), found),
+    };
+    Located {
+        line,
+        column: before[line_start..].chars().count() + 1,
+        message,
+    }
+}
+
+/// Runs `parser` over the whole of `text`, allowing only trailing whitespace after it
+pub fn parse_all<'a, T>(
+    text: &'a str,
+    parser: impl Parser<&'a str, T, Error<'a>>,
+) -> Result<T, Located> {
+    terminated(parser, pair(multispace0, context("end of input", eof)))(text)
+        .finish()
+        .map(|(_, value)| value)
+        .map_err(|err| locate(text, &err))
+}
+
+/// An optionally signed integer or decimal
+pub fn number<T: FromStr>(input: &str) -> IResult<'_, T> {
+    context(
+        "number",
+        map_res(
+            recognize(tuple((
+                opt(alt((char('-'), char('+')))),
+                digit1,
+                opt(pair(char('.'), digit1)),
+            ))),
+            str::parse,
+        ),
+    )(input)
+}
+
+/// Numbers separated by spaces or tabs
+pub fn numbers<T: FromStr>(input: &str) -> IResult<'_, Vec<T>> {
+    separated_list1(space1, number)(input)
+}
+
+/// The `label` of a `label: rest` line, consuming the colon and any spaces after it
+pub fn label(input: &str) -> IResult<'_, &str> {
+    context(
+        "label",
+        terminated(
+            take_till1(|c| c == ':' || c == '\n'),
+            pair(context("':'", char(':')), space0),
+        ),
+    )(input)
+}
+
+/// A `label: rest` line where `rest` is parsed by `f`
+pub fn labelled<'a, O>(
+    f: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<'a, (&'a str, O)> {
+    pair(label, f)
+}
+
+/// Exactly `N` comma separated numbers such as `1,2,3` or `1, 2, 3`
+pub fn vector<T: FromStr, const N: usize>(input: &str) -> IResult<'_, [T; N]> {
+    let (rest, values) = separated_list1(tuple((space0, char(','), space0)), number)(input)?;
+    match <[T; N]>::try_from(values) {
+        Ok(vector) => Ok((rest, vector)),
+        Err(_) => Err(nom::Err::Error(Error {
+            input,
+            kind: ErrorKind::Count,
+            context: Some(match N {
+                2 => "2 comma separated numbers",
+                3 => "3 comma separated numbers",
+                _ => "comma separated numbers",
+            }),
+        })),
+    }
+}
+
+/// A rectangular grid of characters read row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharGrid<T> {
+    pub width: usize,
+    pub height: usize,
+    /// The cells in row major order
+    pub cells: Vec<T>,
+}
+
+impl<T> CharGrid<T> {
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Each cell with its x and y coordinates
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| (idx % self.width, idx / self.width, cell))
+    }
+
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        let mut cells = self.cells.into_iter();
+        (0..self.height)
+            .map(|_| cells.by_ref().take(self.width).collect())
+            .collect()
+    }
+}
+
+fn grid_failure<'a>(input: &'a str, context: &'static str) -> nom::Err<Error<'a>> {
+    nom::Err::Failure(Error {
+        input,
+        kind: ErrorKind::Verify,
+        context: Some(context),
+    })
+}
+
+/// A grid of one character per cell, ending at a blank line or the end of the
+/// input
+///
+/// `cell` turns a character into a cell, or returns `None` if the character
+/// isn't allowed in the grid.
+pub fn char_grid<'a, T>(
+    cell: impl Fn(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<'a, CharGrid<T>> {
+    move |input| {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        let mut rest = input;
+        loop {
+            let (after_row, row) = not_line_ending(rest)?;
+            if row.is_empty() {
+                return Err(nom::Err::Error(Error {
+                    input: rest,
+                    kind: ErrorKind::Many1,
+                    context: Some("grid row"),
+                }));
+            }
+            for (offset, c) in row.char_indices() {
+                cells.push(cell(c).ok_or_else(|| grid_failure(&row[offset..], "grid cell"))?);
+            }
+            let row_width = row.chars().count();
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(grid_failure(row, "row as wide as the first"));
+            }
+            height += 1;
+            rest = after_row;
+            // Only move onto the next line if there's another row on it
+            match line_ending::<_, Error>(rest) {
+                Ok((next, _)) if !next.is_empty() && !next.starts_with(['\n', '\r']) => rest = next,
+                _ => break,
+            }
+        }
+        Ok((
+            rest,
+            CharGrid {
+                width: width.unwrap(),
+                height,
+                cells,
+            },
+        ))
+    }
+}
+
+/// Runs `solve` on each line of `text` and collects the results in order,
+/// spreading the lines over threads with the `parallel` feature
+///
+/// `solve` locates its errors within the line it was given, and they're moved
+/// to where that line is in `text`. If several lines fail the first one is
+/// reported, however the lines were scheduled.
+pub fn solve_lines<'a, T, F>(text: &'a str, solve: F) -> Result<Vec<T>, Located>
+where
+    T: Send,
+    F: Fn(&'a str) -> Result<T, Located> + Sync,
+{
+    let lines: Vec<&'a str> = text.lines().collect();
+    #[cfg(feature = "parallel")]
+    let results: Vec<_> = lines.par_iter().map(|line| solve(line)).collect();
+    #[cfg(not(feature = "parallel"))]
+    let results = lines.iter().map(|line| solve(line));
+    results
+        .into_iter()
+        .enumerate()
+        .map(|(idx, result)| {
+            result.map_err(|err| Located {
+                line: idx + err.line,
+                ..err
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_and_labels() {
+        assert_eq!(
+            parse_all("Time:      7  15   30", labelled(numbers::<u32>)),
+            Ok(("Time", vec![7, 15, 30]))
+        );
+        assert_eq!(
+            parse_all("0 -3 6.5", numbers::<f64>),
+            Ok(vec![0.0, -3.0, 6.5])
+        );
+        assert_eq!(parse_all("19, 13, 30", vector::<i64, 3>), Ok([19, 13, 30]));
+        assert_eq!(parse_all("1,0,1\n", vector::<i32, 3>), Ok([1, 0, 1]));
+    }
+
+    #[test]
+    fn located_errors() {
+        let err = parse_all("1 2\n3 x", separated_list1(line_ending, numbers::<u32>)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.message, "expected end of input, found 'x'");
+        let err = parse_all("1,2", vector::<i32, 3>).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(err.message, "expected 3 comma separated numbers, found '1'");
+        let err = parse_all("Card 1 41", labelled(numbers::<u32>)).unwrap_err();
+        assert_eq!(err.message, "expected ':', found end of input");
+        let err = parse_all(": 41", labelled(numbers::<u32>)).unwrap_err();
+        assert_eq!(err.message, "expected label, found ':'");
+    }
+
+    #[test]
+    fn lines() {
+        let text = "1 2\n3 4\n5 x\n7 y\n";
+        let sum_line = |line: &str| parse_all(line, numbers::<u32>).map(|n| n.iter().sum::<u32>());
+        assert_eq!(solve_lines(&text[..8], sum_line), Ok(vec![3, 7]));
+        let err = solve_lines(text, sum_line).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+
+    #[test]
+    fn grids() {
+        let cell = |c| match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None,
+        };
+        let grids = parse_all(
+            "#.\n.#\n\n##\n..\n",
+            separated_list1(tuple((line_ending, line_ending)), char_grid(cell)),
+        )
+        .unwrap();
+        assert_eq!(grids.len(), 2);
+        assert_eq!((grids[0].width, grids[0].height), (2, 2));
+        assert_eq!(grids[0].get(1, 1), Some(&true));
+        assert_eq!(
+            grids[1].clone().into_rows(),
+            vec![vec![true, true], vec![false, false]]
+        );
+        let err = parse_all("#.\n.x", char_grid(cell)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = parse_all("#.\n.", char_grid(cell)).unwrap_err();
+        assert_eq!(err.message, "expected row as wide as the first, found '.'");
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use common::context::SolverContext;
+use pyo3::exceptions::{PyRuntimeError, PyTimeoutError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyFloat, PyInt};
+use solvers::{Error, Param, Params};
+
+fn to_py_err(err: Error) -> PyErr {
+    match err {
+        Error::UnknownSolver { .. }
+        | Error::UnknownVariant { .. }
+        | Error::BadParam(_)
+        | Error::BadInput(_) => PyValueError::new_err(err.to_string()),
+        Error::Interrupted(_) => PyTimeoutError::new_err(err.to_string()),
+        Error::Checkpoint(_) | Error::Panicked(_) => PyRuntimeError::new_err(err.to_string()),
+    }
+}
+
+fn to_params(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Params> {
+    let mut params = Params::new();
+    let Some(kwargs) = kwargs else {
+        return Ok(params);
+    };
+    for (name, value) in kwargs.iter() {
+        let name: String = name.extract()?;
+        let param = if value.is_instance_of::<PyInt>() {
+            Param::Int(value.extract()?)
+        } else if value.is_instance_of::<PyFloat>() {
+            Param::Float(value.extract()?)
+        } else {
+            return Err(PyTypeError::new_err(format!(
+                "parameter {} must be an int or a float",
+                name
+            )));
+        };
+        params.insert(name, param);
+    }
+    Ok(params)
+}
+
+/// Solve one part of a day's puzzle, passing any puzzle parameters as keyword arguments
+///
+/// With a `timeout` in seconds, solvers that check for it raise `TimeoutError`
+/// once it runs out.
+#[pyfunction]
+#[pyo3(signature = (day, part, text, *, timeout=None, **params))]
+fn solve(
+    py: Python<'_>,
+    day: u32,
+    part: u32,
+    text: &str,
+    timeout: Option<f64>,
+    params: Option<&Bound<'_, PyDict>>,
+) -> PyResult<i64> {
+    let params = to_params(params)?;
+    let mut ctx = SolverContext::new();
+    if let Some(timeout) = timeout {
+        let timeout = Duration::try_from_secs_f64(timeout)
+            .map_err(|_| PyValueError::new_err("timeout must be a non-negative number"))?;
+        ctx = ctx.with_timeout(timeout);
+    }
+    py.detach(|| solvers::solve_with_context(day, part, text, &params, &ctx))
+        .map_err(to_py_err)
+}
+
+/// List the registered solvers as (day, part) pairs
+#[pyfunction]
+fn registered() -> Vec<(u32, u32)> {
+    solvers::days()
+        .into_iter()
+        .flat_map(|day| solvers::parts(day).into_iter().map(move |part| (day, part)))
+        .collect()
+}
+
+/// The day 19 workflows keyed by name, each a list of rule dicts
+#[pyfunction]
+fn day19_workflows<'py>(py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyDict>> {
+    let (workflows, _) =
+        day19::parse_input(text).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    let dict = PyDict::new(py);
+    for (name, workflow) in workflows {
+        let mut rules = Vec::new();
+        for rule in workflow.rules {
+            let rule_dict = PyDict::new(py);
+            match rule.condition {
+                Some((var, op, num)) => {
+                    let condition = PyDict::new(py);
+                    condition.set_item("var", var)?;
+                    condition.set_item("op", op)?;
+                    condition.set_item("value", num)?;
+                    rule_dict.set_item("condition", condition)?;
+                }
+                None => rule_dict.set_item("condition", py.None())?,
+            }
+            let dest = match rule.dest {
+                day19::Destination::A => "A",
+                day19::Destination::R => "R",
+                day19::Destination::Workflow(w) => w,
+            };
+            rule_dict.set_item("dest", dest)?;
+            rules.push(rule_dict);
+        }
+        dict.set_item(name, rules)?;
+    }
+    Ok(dict)
+}
+
+/// The day 19 parts as dicts of their x, m, a and s ratings
+#[pyfunction]
+fn day19_parts(text: &str) -> PyResult<Vec<HashMap<char, u32>>> {
+    let (_, parts) =
+        day19::parse_input(text).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(parts
+        .into_iter()
+        .map(|part| HashMap::from([('x', part.x), ('m', part.m), ('a', part.a), ('s', part.s)]))
+        .collect())
+}
+
+type Cube = (i32, i32, i32);
+
+/// The day 22 bricks as (start, end) pairs, in the order used by the support map
+#[pyfunction]
+fn day22_bricks(text: &str) -> PyResult<Vec<(Cube, Cube)>> {
+    let (_, bricks) =
+        day22::parse_bricks(text).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(day22::sort_bricks(bricks)
+        .into_iter()
+        .map(|brick| (brick.start.into(), brick.end.into()))
+        .collect())
+}
+
+/// Map from each day 22 brick to the set of bricks it rests on
+#[pyfunction]
+fn day22_support_map(text: &str) -> PyResult<HashMap<usize, HashSet<usize>>> {
+    let (_, bricks) =
+        day22::parse_bricks(text).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(day22::support_map(&day22::sort_bricks(bricks)))
+}
+
+/// The day 25 wiring diagram as a list of component pairs
+#[pyfunction]
+fn day25_edges(text: &str) -> PyResult<Vec<(String, String)>> {
+    let edges = day25::edges(text).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(edges
+        .into_iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect())
+}
+
+#[pymodule]
+fn aoc(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(registered, m)?)?;
+    m.add_function(wrap_pyfunction!(day19_workflows, m)?)?;
+    m.add_function(wrap_pyfunction!(day19_parts, m)?)?;
+    m.add_function(wrap_pyfunction!(day22_bricks, m)?)?;
+    m.add_function(wrap_pyfunction!(day22_support_map, m)?)?;
+    m.add_function(wrap_pyfunction!(day25_edges, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+import pathlib
+import unittest
+
+import aoc
+
+ROOT = pathlib.Path(__file__).resolve().parents[2]
+
+
+def example(day, part):
+    return (ROOT / f"day{day}" / "data" / f"part{part}_example.txt").read_text()
+
+
+class SolveTest(unittest.TestCase):
+    def test_example(self):
+        self.assertEqual(aoc.solve(1, 1, example(1, 1)), 142)
+
+    def test_params(self):
+        self.assertEqual(aoc.solve(21, 1, example(21, 1), steps=6), 16)
+        self.assertEqual(aoc.solve(24, 1, example(24, 1), min=7, max=27.0), 2)
+
+    def test_errors(self):
+        with self.assertRaises(ValueError):
+            aoc.solve(26, 1, "")
+        with self.assertRaises(ValueError):
+            aoc.solve(1, 1, example(1, 1), steps=6)
+        with self.assertRaises(ValueError):
+            aoc.solve(1, 1, "1\nabc\n")
+        with self.assertRaises(TypeError):
+            aoc.solve(21, 1, example(21, 1), steps="6")
+        with self.assertRaises(RuntimeError):
+            aoc.solve(24, 2, example(24, 2))
+        with self.assertRaises(ValueError):
+            aoc.solve(1, 1, example(1, 1), timeout=-1.0)
+
+    def test_timeout(self):
+        text = (ROOT / "day23" / "data" / "input.txt").read_text()
+        with self.assertRaises(TimeoutError):
+            aoc.solve(23, 2, text, timeout=0.05)
+        self.assertEqual(aoc.solve(1, 1, example(1, 1), timeout=10.0), 142)
+
+    def test_registered(self):
+        self.assertIn((25, 2), aoc.registered())
+
+
+class ModelTest(unittest.TestCase):
+    def test_day19_workflows(self):
+        workflows = aoc.day19_workflows(example(19, 1))
+        self.assertEqual(
+            workflows["pv"],
+            [
+                {"condition": {"var": "a", "op": ">", "value": 1716}, "dest": "R"},
+                {"condition": None, "dest": "A"},
+            ],
+        )
+
+    def test_day19_parts(self):
+        parts = aoc.day19_parts(example(19, 1))
+        self.assertEqual(parts[0], {"x": 787, "m": 2655, "a": 1222, "s": 2876})
+
+    def test_day22_support_map(self):
+        bricks = aoc.day22_bricks(example(22, 1))
+        support_map = aoc.day22_support_map(example(22, 1))
+        self.assertEqual(bricks[0], ((1, 0, 1), (1, 2, 1)))
+        self.assertEqual(support_map[0], set())
+        self.assertEqual(support_map[1], {0})
+        self.assertEqual(support_map[3], {1, 2})
+
+    def test_day25_edges(self):
+        edges = aoc.day25_edges(example(25, 1))
+        self.assertEqual(len(edges), 33)
+        self.assertIn(("jqt", "rhn"), edges)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,256 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use common::hash::{self, StableHasher};
+use serde::{Deserialize, Serialize};
+use solvers::{Answer, Params, Solver, SolverContext};
+
+use crate::alloc::{self, Memory};
+use crate::Run;
+
+/// The crates besides the day's own whose code can change an answer
+const SHARED_CRATES: &[&str] = &["common", "parsing", "solvers", "runner"];
+
+/// Answers from earlier runs, saved so a part whose input, parameters and
+/// code haven't changed doesn't have to be solved again
+///
+/// Only successful runs are kept, along with how long they originally took
+/// and their heap use. A run cached without its heap use is run again when
+/// allocations are being counted. Entries for code that has since changed are
+/// dropped when the cache is saved.
+pub struct Cache {
+    path: PathBuf,
+    entries: BTreeMap<String, Entry>,
+    sources: HashMap<u32, u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// The day and source hash the answer was found with
+    day: u32,
+    source: u64,
+    answer: Answer,
+    elapsed: Duration,
+    #[serde(default)]
+    memory: Option<Memory>,
+}
+
+/// Hashes every file under `path` along with its name relative to `root`,
+/// in a fixed order
+fn hash_tree(root: &Path, path: &Path, hasher: &mut StableHasher) -> io::Result<()> {
+    if path.is_file() {
+        let name = path.strip_prefix(root).unwrap_or(path);
+        hasher.write_field(name.to_string_lossy().as_bytes());
+        hasher.write_field(&fs::read(path)?);
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    entries
+        .iter()
+        .try_for_each(|entry| hash_tree(root, entry, hasher))
+}
+
+/// A hash of the code and dependency versions behind a day's answers, or of
+/// the runner's version if the sources can't be read
+fn source_hash(day: u32) -> u64 {
+    let day_dir = crate::day_dir(day);
+    let workspace = day_dir.join("..");
+    let mut hasher = StableHasher::new();
+    hasher.write_field(hash::ALGORITHM.as_bytes());
+    hasher.write_field(env!("CARGO_PKG_VERSION").as_bytes());
+    let paths = [
+        day_dir.join("src"),
+        day_dir.join("Cargo.toml"),
+        workspace.join("Cargo.lock"),
+    ]
+    .into_iter()
+    .chain(
+        SHARED_CRATES
+            .iter()
+            .map(|name| workspace.join(name).join("src")),
+    );
+    for path in paths {
+        if hash_tree(&workspace, &path, &mut hasher).is_err() {
+            return hash::stable_hash(env!("CARGO_PKG_VERSION").as_bytes());
+        }
+    }
+    hasher.finish()
+}
+
+impl Cache {
+    /// The cache saved in the workspace's `target` directory
+    pub fn default_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target/runner-cache.json")
+    }
+
+    /// Reads the cache, starting an empty one if the file is missing or
+    /// can't be read
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries,
+            sources: HashMap::new(),
+        }
+    }
+
+    fn source(&mut self, day: u32) -> u64 {
+        *self.sources.entry(day).or_insert_with(|| source_hash(day))
+    }
+
+    /// The key for running `solver` on `text`, or `None` if the params don't
+    /// fit its schema
+    fn key(&mut self, solver: &Solver, text: &str, params: &Params) -> Option<String> {
+        let mut params: Vec<String> = solver
+            .resolve(params)
+            .ok()?
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        params.sort();
+        let mut params_hasher = StableHasher::new();
+        params
+            .iter()
+            .for_each(|param| params_hasher.write_field(param.as_bytes()));
+        Some(format!(
+            "day{}-part{}-{}-{:016x}-{:016x}-{:016x}",
+            solver.day,
+            solver.part,
+            solver.variant,
+            hash::stable_hash(text.as_bytes()),
+            params_hasher.finish(),
+            self.source(solver.day)
+        ))
+    }
+
+    /// Returns the cached run if there is one, otherwise runs the solver and
+    /// remembers its answer. `force` always runs it.
+    pub fn run(
+        &mut self,
+        solver: &Solver,
+        text: &str,
+        params: &Params,
+        ctx: &SolverContext,
+        force: bool,
+    ) -> Run {
+        let key = self.key(solver, text, params);
+        if let Some(entry) = key
+            .as_ref()
+            .filter(|_| !force)
+            .and_then(|key| self.entries.get(key))
+            .filter(|entry| entry.memory.is_some() || !alloc::enabled())
+        {
+            return Run {
+                day: solver.day,
+                part: solver.part,
+                variant: solver.variant,
+                result: Ok(entry.answer),
+                elapsed: entry.elapsed,
+                cached: true,
+                memory: entry.memory,
+            };
+        }
+        let run = crate::run(solver, text, params, ctx);
+        if let (Some(key), Ok(answer)) = (key, &run.result) {
+            let source = self.source(solver.day);
+            self.entries.insert(
+                key,
+                Entry {
+                    day: solver.day,
+                    source,
+                    answer: *answer,
+                    elapsed: run.elapsed,
+                    memory: run.memory,
+                },
+            );
+        }
+        run
+    }
+
+    /// Drops the entries whose day's code has changed since they were saved
+    pub fn prune(&mut self) {
+        let mut entries = std::mem::take(&mut self.entries);
+        entries.retain(|_, entry| entry.source == self.source(entry.day));
+        self.entries = entries;
+    }
+
+    /// Prunes out of date entries, writes the cache next to its file and then
+    /// moves it into place
+    pub fn save(&mut self) -> io::Result<()> {
+        self.prune();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string(&self.entries)?)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_answers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runner-cache.json");
+        let text = fs::read_to_string("../day11/data/part1_example.txt").unwrap();
+        let solver = solvers::find(11, 2).unwrap();
+        let ctx = SolverContext::new();
+        let params = Params::from([("expansion".to_string(), solvers::Param::Int(10))]);
+
+        let mut cache = Cache::open(&path);
+        let first = cache.run(solver, &text, &params, &ctx, false);
+        assert_eq!((first.result.clone(), first.cached), (Ok(1030), false));
+        cache.save().unwrap();
+
+        let mut cache = Cache::open(&path);
+        let again = cache.run(solver, &text, &params, &ctx, false);
+        assert_eq!((again.result, again.cached), (Ok(1030), true));
+        assert_eq!(again.elapsed, first.elapsed);
+        let other = Params::from([("expansion".to_string(), solvers::Param::Int(100))]);
+        assert_eq!(
+            cache.run(solver, &text, &other, &ctx, false).result,
+            Ok(8410)
+        );
+        assert!(!cache.run(solver, "#.\n..\n", &params, &ctx, false).cached);
+        assert!(!cache.run(solver, &text, &params, &ctx, true).cached);
+    }
+
+    #[test]
+    fn prunes_stale_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runner-cache.json");
+        let text = fs::read_to_string("../day11/data/part1_example.txt").unwrap();
+        let solver = solvers::find(11, 1).unwrap();
+        let ctx = SolverContext::new();
+
+        let mut cache = Cache::open(&path);
+        cache.run(solver, &text, &Params::new(), &ctx, false);
+        let stale = Entry {
+            day: 11,
+            source: !cache.source(11),
+            answer: 0,
+            elapsed: Duration::ZERO,
+            memory: None,
+        };
+        cache.entries.insert("stale".to_string(), stale);
+        cache.save().unwrap();
+
+        let mut cache = Cache::open(&path);
+        assert_eq!(cache.entries.len(), 1);
+        assert!(!cache.entries.contains_key("stale"));
+        assert!(cache.run(solver, &text, &Params::new(), &ctx, false).cached);
+    }
+}
@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::Path;
+
+use scraper::{ElementRef, Html, Selector};
+use solvers::Answer;
+
+use crate::manifest;
+
+/// An example input from a puzzle page and the answer the text gives for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub part: u32,
+    pub input: String,
+    pub answer: Answer,
+}
+
+fn inside(element: ElementRef, name: &str) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|ancestor| ancestor.value().name() == name)
+}
+
+/// Finds each part's example in a puzzle page saved from the site
+///
+/// Each part is an `article.day-desc`. Its answer is the last emphasized
+/// number in inline code, and its example is the last `<pre><code>` block
+/// before that. A part without a block of its own reuses the previous part's.
+pub fn examples(html: &str) -> Vec<Example> {
+    let document = Html::parse_document(html);
+    let articles = Selector::parse("article.day-desc").unwrap();
+    let mut examples: Vec<Example> = Vec::new();
+    for (idx, article) in document.select(&articles).enumerate() {
+        let mut block = examples.last().map(|example| example.input.clone());
+        let mut found = None;
+        for element in article.descendants().filter_map(ElementRef::wrap) {
+            match element.value().name() {
+                "pre" => block = Some(element.text().collect()),
+                "em" if inside(element, "code") && !inside(element, "pre") => {
+                    let text: String = element.text().collect();
+                    if let (Ok(answer), Some(block)) = (text.trim().parse(), &block) {
+                        found = Some((block.clone(), answer));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some((input, answer)) = found {
+            examples.push(Example {
+                part: idx as u32 + 1,
+                input,
+                answer,
+            });
+        }
+    }
+    examples
+}
+
+/// Writes the examples into a day's `data` directory and adds them to its
+/// test manifest, returning the files that were written
+///
+/// An existing example file is only replaced if `force` is set.
+pub fn save(data_dir: &Path, examples: &[Example], force: bool) -> Result<Vec<String>, String> {
+    let mut written: Vec<(String, &str)> = Vec::new();
+    let mut answers = Vec::new();
+    for example in examples {
+        let name = match written.iter().find(|(_, input)| *input == example.input) {
+            Some((name, _)) => name.clone(),
+            None => {
+                let name = format!("part{}_example.txt", example.part);
+                let path = data_dir.join(&name);
+                match fs::read_to_string(&path) {
+                    Ok(existing) if existing != example.input && !force => {
+                        return Err(format!(
+                            "{} already exists with a different example, use --force to replace it",
+                            path.display()
+                        ))
+                    }
+                    _ => fs::write(&path, &example.input)
+                        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?,
+                }
+                written.push((name.clone(), &example.input));
+                name
+            }
+        };
+        answers.push((example.part, name, example.answer));
+    }
+    manifest::record(&data_dir.join("tests.toml"), &answers)?;
+    Ok(written.into_iter().map(|(name, _)| name).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Trebuchet?! ---</h2>
+<p>For example:</p>
+<pre><code>1abc2
+pqr3stu8vwx
+</code></pre>
+<p>Here, the values are <code>12</code> and <code>38</code>.
+Adding these together produces <code><em>50</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>54968</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>It looks like <em>some</em> of the digits are spelled out, for example:</p>
+<pre><code>two1nine
+<em>eight</em>wothree
+</code></pre>
+<p>In this example, the total is <code><em>112</em></code>.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn finds_examples() {
+        assert_eq!(
+            examples(PAGE),
+            vec![
+                Example {
+                    part: 1,
+                    input: "1abc2\npqr3stu8vwx\n".to_string(),
+                    answer: 50,
+                },
+                Example {
+                    part: 2,
+                    input: "two1nine\neightwothree\n".to_string(),
+                    answer: 112,
+                },
+            ]
+        );
+        let part1_only = PAGE.replace(
+            "<pre><code>two1nine\n<em>eight</em>wothree\n</code></pre>",
+            "",
+        );
+        assert_eq!(examples(&part1_only)[1].input, "1abc2\npqr3stu8vwx\n");
+    }
+
+    #[test]
+    fn saves_examples() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(
+            dir.join("tests.toml"),
+            "[[case]]\npart = 2\ninput = \"part1_example.txt\"\nparams = { n = 2 }\nanswer = 7\n",
+        )
+        .unwrap();
+        let mut found = examples(PAGE);
+        found[1].input = found[0].input.clone();
+        assert_eq!(
+            save(dir, &found, false),
+            Ok(vec!["part1_example.txt".to_string()])
+        );
+        let cases = manifest::read(&dir.join("tests.toml")).unwrap();
+        assert_eq!(cases.len(), 3);
+        assert_eq!((cases[1].part, cases[1].answer), (1, 50));
+        assert_eq!(
+            (cases[2].input.as_str(), cases[2].answer),
+            ("part1_example.txt", 112)
+        );
+        found[0].input = "changed\n".to_string();
+        assert!(save(dir, &found[..1], false).is_err());
+        assert!(save(dir, &found[..1], true).is_ok());
+        assert_eq!(manifest::read(&dir.join("tests.toml")).unwrap().len(), 3);
+        assert!(fs::read_to_string(dir.join("tests.toml"))
+            .unwrap()
+            .starts_with(
+                "[[case]]\npart = 2\ninput = \"part1_example.txt\"\nparams = { n = 2 }\n"
+            ));
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use solvers::{Answer, Error, Params, Solver, SolverContext};
+
+use crate::alloc::Memory;
+
+pub mod alloc;
+pub mod cache;
+pub mod extract;
+pub mod manifest;
+pub mod repl;
+
+/// The day's crate in the workspace
+pub fn day_dir(day: u32) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day{}", day))
+}
+
+pub fn input_path(day: u32) -> PathBuf {
+    day_dir(day).join("data/input.txt")
+}
+
+/// The outcome of running one variant of a part
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub day: u32,
+    pub part: u32,
+    pub variant: &'static str,
+    pub result: Result<Answer, Error>,
+    pub elapsed: Duration,
+    /// Whether the answer and time came from an earlier run
+    pub cached: bool,
+    /// The run's heap use, if allocations are being counted
+    pub memory: Option<Memory>,
+}
+
+pub fn run(solver: &Solver, text: &str, params: &Params, ctx: &SolverContext) -> Run {
+    let start = Instant::now();
+    let (result, memory) = alloc::measure(|| solver.run(text, params, ctx));
+    Run {
+        day: solver.day,
+        part: solver.part,
+        variant: solver.variant,
+        result,
+        elapsed: start.elapsed(),
+        cached: false,
+        memory,
+    }
+}
+
+/// The params that `solver` accepts, so one set of params can be shared by
+/// parts with different schemas
+pub fn params_for(solver: &Solver, params: &Params) -> Params {
+    params
+        .iter()
+        .filter(|(name, _)| solver.params.iter().any(|spec| spec.name == name.as_str()))
+        .map(|(name, value)| (name.clone(), *value))
+        .collect()
+}
+
+/// Whether any part of the day takes a parameter called `name`
+pub fn takes_param(day: u32, name: &str) -> bool {
+    solvers::parts(day).into_iter().any(|part| {
+        solvers::variants(day, part)
+            .any(|solver| solver.params.iter().any(|spec| spec.name == name))
+    })
+}
+
+/// One line per parameter of the day's parts, with its default and what it's for
+pub fn schema(day: u32) -> String {
+    let mut out = String::new();
+    for part in solvers::parts(day) {
+        for spec in solvers::find(day, part).unwrap().params {
+            writeln!(
+                out,
+                "day {} part {}: {} = {} ({}), {}",
+                day, part, spec.name, spec.default, spec.kind, spec.help
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+/// Groups the runs by day and part, keeping the order they were run in
+fn by_part(runs: &[Run]) -> BTreeMap<(u32, u32), Vec<&Run>> {
+    let mut parts: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for run in runs {
+        parts.entry((run.day, run.part)).or_default().push(run);
+    }
+    parts
+}
+
+/// The parts whose variants finished with different answers
+pub fn disagreements(runs: &[Run]) -> Vec<(u32, u32)> {
+    by_part(runs)
+        .into_iter()
+        .filter(|(_, runs)| {
+            let mut answers = runs.iter().filter_map(|run| run.result.as_ref().ok());
+            answers
+                .next()
+                .is_some_and(|first| answers.any(|answer| answer != first))
+        })
+        .map(|(part, _)| part)
+        .collect()
+}
+
+/// How many times slower each run was than the fastest variant of its part,
+/// or `None` if the part only had the one variant
+fn relative_times(runs: &[Run]) -> Vec<Option<f64>> {
+    let parts = by_part(runs);
+    runs.iter()
+        .map(|run| {
+            let variants = &parts[&(run.day, run.part)];
+            if variants.len() < 2 {
+                return None;
+            }
+            let fastest = variants.iter().map(|run| run.elapsed).min().unwrap();
+            Some(run.elapsed.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON))
+        })
+        .collect()
+}
+
+pub fn table(runs: &[Run]) -> String {
+    let with_memory = runs.iter().any(|run| run.memory.is_some());
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .zip(relative_times(runs))
+        .map(|(run, relative)| {
+            let mut row = vec![
+                run.day.to_string(),
+                run.part.to_string(),
+                run.variant.to_string(),
+                match &run.result {
+                    Ok(answer) => answer.to_string(),
+                    Err(err) => format!("error: {}", err),
+                },
+                if run.cached {
+                    format!("{:.3?} (cached)", run.elapsed)
+                } else {
+                    format!("{:.3?}", run.elapsed)
+                },
+                relative.map_or(String::new(), |relative| format!("{:.2}x", relative)),
+            ];
+            if with_memory {
+                row.extend(match run.memory {
+                    Some(memory) => [
+                        alloc::format_bytes(memory.peak_bytes),
+                        memory.allocations.to_string(),
+                    ],
+                    None => [String::new(), String::new()],
+                });
+            }
+            row
+        })
+        .collect();
+    let mut header = vec!["day", "part", "variant", "answer", "time", "relative"];
+    if with_memory {
+        header.extend(["peak heap", "allocations"]);
+    }
+    let header: Vec<String> = header.into_iter().map(String::from).collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .chain([&header])
+                .map(|row| row[col].len())
+                .max()
+                .unwrap()
+        })
+        .collect();
+    let mut out = String::new();
+    for row in [&header].into_iter().chain(&rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    for (day, part) in disagreements(runs) {
+        writeln!(out, "day {} part {}: variants disagree", day, part).unwrap();
+    }
+    out
+}
+
+pub fn to_json(runs: &[Run]) -> Value {
+    let runs_json: Vec<Value> = runs
+        .iter()
+        .zip(relative_times(runs))
+        .map(|(run, relative)| {
+            let mut value = json!({
+                "day": run.day,
+                "part": run.part,
+                "variant": run.variant,
+                "elapsed_ms": run.elapsed.as_secs_f64() * 1000.0,
+                "relative": relative,
+                "cached": run.cached,
+            });
+            if let Some(memory) = run.memory {
+                value["peak_heap_bytes"] = json!(memory.peak_bytes);
+                value["allocations"] = json!(memory.allocations);
+            }
+            match &run.result {
+                Ok(answer) => value["answer"] = json!(answer),
+                Err(err) => value["error"] = json!(err.to_string()),
+            }
+            value
+        })
+        .collect();
+    let disagreements: Vec<Value> = disagreements(runs)
+        .into_iter()
+        .map(|(day, part)| json!({ "day": day, "part": part }))
+        .collect();
+    json!({ "runs": runs_json, "disagreements": disagreements })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_run(part: u32, variant: &'static str, answer: Answer, millis: u64) -> Run {
+        Run {
+            day: 1,
+            part,
+            variant,
+            result: Ok(answer),
+            elapsed: Duration::from_millis(millis),
+            cached: false,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn agreement() {
+        let runs = [
+            fake_run(1, "slow", 5, 40),
+            fake_run(1, "fast", 5, 10),
+            fake_run(2, "slow", 7, 40),
+            fake_run(2, "fast", 8, 10),
+        ];
+        assert_eq!(disagreements(&runs), vec![(1, 2)]);
+        assert_eq!(relative_times(&runs[..2]), vec![Some(4.0), Some(1.0)]);
+        assert_eq!(relative_times(&runs[..1]), vec![None]);
+        let json = to_json(&runs);
+        assert_eq!(json["runs"][0]["answer"], 5);
+        assert_eq!(json["disagreements"], json!([{ "day": 1, "part": 2 }]));
+        assert!(table(&runs).ends_with("day 1 part 2: variants disagree\n"));
+        assert!(!table(&runs).contains("peak heap"));
+
+        let mut measured = fake_run(1, "fast", 5, 10);
+        measured.memory = Some(Memory {
+            peak_bytes: 3 << 19,
+            allocations: 12,
+        });
+        assert!(table(&[measured.clone()]).contains("1.5 MiB"));
+        assert_eq!(to_json(&[measured])["runs"][0]["allocations"], 12);
+    }
+
+    #[test]
+    fn runs_solvers() {
+        let text = std::fs::read_to_string("../day18/data/part1_example.txt").unwrap();
+        let runs: Vec<Run> = solvers::variants(18, 1)
+            .map(|solver| run(solver, &text, &Params::new(), &SolverContext::new()))
+            .collect();
+        assert_eq!(runs.len(), 2);
+        assert!(runs.iter().all(|run| run.result == Ok(62)));
+        assert!(disagreements(&runs).is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use runner::cache::Cache;
+use runner::repl::Session;
+use runner::{extract, input_path, manifest};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use solvers::{Param, Params, Solver, SolverContext};
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: runner [DAY [PART]] [--variant NAME | --all-variants] [--input PATH] \
+         [--param NAME=VALUE]... [--timeout SECONDS] [--force] [--json]\n       \
+         runner repl DAY [--input PATH] [--timeout SECONDS]\n       \
+         runner test [DAY]\n       \
+         runner extract DAY PAGE.html [--force]\n       \
+         runner params [DAY]"
+    );
+    process::exit(2);
+}
+
+fn repl(mut args: impl Iterator<Item = String>) {
+    let day = args
+        .next()
+        .and_then(|day| day.parse().ok())
+        .unwrap_or_else(|| usage());
+    let mut path = None;
+    let mut timeout = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => path = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--timeout" => {
+                let secs = args
+                    .next()
+                    .and_then(|secs| secs.parse::<f64>().ok())
+                    .unwrap_or_else(|| usage());
+                timeout = Some(Duration::from_secs_f64(secs));
+            }
+            _ => usage(),
+        }
+    }
+    let path = path.unwrap_or_else(|| input_path(day));
+    let mut session = Session::load(day, &path)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        })
+        .with_timeout(timeout);
+    runner::repl::cancel_on_ctrl_c();
+    let mut editor = DefaultEditor::new().unwrap();
+    loop {
+        let line = match editor.readline(&session.prompt()) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("Failed to read command: {}", err);
+                process::exit(1);
+            }
+        };
+        if matches!(line.trim(), "quit" | "exit") {
+            break;
+        }
+        let _ = editor.add_history_entry(line.as_str());
+        match session.execute(&line) {
+            Ok(out) if out.is_empty() || out.ends_with('\n') => print!("{}", out),
+            Ok(out) => println!("{}", out),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+}
+
+/// Checks the examples in the days' test manifests
+fn test(mut args: impl Iterator<Item = String>) {
+    let days = match args.next() {
+        None => solvers::days(),
+        Some(day) => vec![day.parse().unwrap_or_else(|_| usage())],
+    };
+    let mut failed = 0;
+    for day in days {
+        let cases = manifest::load(day).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        });
+        for case in cases {
+            let outcome = match manifest::check(day, &case) {
+                Ok(run) if run.result == Ok(case.answer) => format!("ok ({:.3?})", run.elapsed),
+                Ok(run) => match run.result {
+                    Ok(answer) => format!("FAILED: expected {}, got {}", case.answer, answer),
+                    Err(err) => format!("FAILED: {}", err),
+                },
+                Err(err) => format!("FAILED: {}", err),
+            };
+            if outcome.starts_with("FAILED") {
+                failed += 1;
+            }
+            println!(
+                "day {} part {} {}: {}",
+                day,
+                case.part,
+                case.label(),
+                outcome
+            );
+        }
+    }
+    if failed > 0 {
+        eprintln!("{} cases failed", failed);
+        process::exit(1);
+    }
+}
+
+/// Pulls the examples out of a saved puzzle page into the day's data directory
+fn extract(args: impl Iterator<Item = String>) {
+    let args: Vec<String> = args.collect();
+    let (day, page, force) = match &args[..] {
+        [day, page] => (day, page, false),
+        [day, page, flag] if flag == "--force" => (day, page, true),
+        _ => usage(),
+    };
+    let day: u32 = day.parse().unwrap_or_else(|_| usage());
+    let html = fs::read_to_string(page).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {}", page, err);
+        process::exit(1);
+    });
+    let examples = extract::examples(&html);
+    if examples.is_empty() {
+        eprintln!("No examples with answers found in {}", page);
+        process::exit(1);
+    }
+    let data_dir = runner::day_dir(day).join("data");
+    match extract::save(&data_dir, &examples, force) {
+        Ok(written) => {
+            for name in written {
+                println!("wrote {}", data_dir.join(name).display());
+            }
+            for example in examples {
+                println!("part {}: expecting {}", example.part, example.answer);
+            }
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let mut positional = Vec::new();
+    let mut variant = None;
+    let mut all_variants = false;
+    let mut input = None;
+    let mut timeout = None;
+    let mut json = false;
+    let mut force = false;
+    let mut params = Params::new();
+    let mut args = env::args().skip(1).peekable();
+    match args.peek().map(String::as_str) {
+        Some("repl") => return repl(args.skip(1)),
+        Some("test") => return test(args.skip(1)),
+        Some("extract") => return extract(args.skip(1)),
+        Some("params") => {
+            let days = match args.nth(1) {
+                None => solvers::days(),
+                Some(day) => vec![day.parse().unwrap_or_else(|_| usage())],
+            };
+            days.into_iter()
+                .for_each(|day| print!("{}", runner::schema(day)));
+            return;
+        }
+        _ => {}
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--variant" => variant = Some(args.next().unwrap_or_else(|| usage())),
+            "--all-variants" => all_variants = true,
+            "--input" => input = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "--timeout" => {
+                let secs = args
+                    .next()
+                    .and_then(|secs| secs.parse::<f64>().ok())
+                    .unwrap_or_else(|| usage());
+                timeout = Some(Duration::from_secs_f64(secs));
+            }
+            "--param" => {
+                let (name, value) = args
+                    .next()
+                    .and_then(|param| {
+                        let (name, value) = param.split_once('=')?;
+                        Some((name.to_string(), value.parse::<Param>().ok()?))
+                    })
+                    .unwrap_or_else(|| usage());
+                params.insert(name, value);
+            }
+            "--force" => force = true,
+            "--json" => json = true,
+            _ => positional.push(arg.parse::<u32>().unwrap_or_else(|_| usage())),
+        }
+    }
+    let (days, part) = match positional[..] {
+        [] => (solvers::days(), None),
+        [day] => (vec![day], None),
+        [day, part] => (vec![day], Some(part)),
+        _ => usage(),
+    };
+    if (variant.is_some() && all_variants) || (input.is_some() && days.len() != 1) {
+        usage();
+    }
+
+    if let Some(name) = params
+        .keys()
+        .find(|name| !days.iter().any(|&day| runner::takes_param(day, name)))
+    {
+        eprintln!("no part takes a parameter {}", name);
+        process::exit(1);
+    }
+
+    let mut cache = Cache::open(Cache::default_path());
+    let mut runs = Vec::new();
+    for day in days {
+        let path = input.clone().unwrap_or_else(|| input_path(day));
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("Failed to read {}: {}", path.display(), err);
+                process::exit(1);
+            }
+        };
+        let parts = part.map_or_else(|| solvers::parts(day), |part| vec![part]);
+        for part in parts {
+            let selected: Vec<&Solver> = match &variant {
+                Some(name) => vec![
+                    solvers::find_variant(day, part, name).unwrap_or_else(|err| {
+                        eprintln!("{}", err);
+                        process::exit(1);
+                    }),
+                ],
+                None if all_variants => solvers::variants(day, part).collect(),
+                None => solvers::find(day, part).into_iter().collect(),
+            };
+            if selected.is_empty() {
+                eprintln!("no solver registered for day {} part {}", day, part);
+                process::exit(1);
+            }
+            for solver in selected {
+                let mut ctx = SolverContext::new();
+                if let Some(timeout) = timeout {
+                    ctx = ctx.with_timeout(timeout);
+                }
+                let params = runner::params_for(solver, &params);
+                runs.push(cache.run(solver, &text, &params, &ctx, force));
+            }
+        }
+    }
+    if let Err(err) = cache.save() {
+        eprintln!("Failed to save the result cache: {}", err);
+    }
+
+    if json {
+        println!("{}", runner::to_json(&runs));
+    } else {
+        print!("{}", runner::table(&runs));
+    }
+    if runs.iter().any(|run| run.result.is_err()) || !runner::disagreements(&runs).is_empty() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use solvers::{Answer, Param, Params, SolverContext};
+
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::Run;
+
+/// An example input and the answer the puzzle gives for it, listed in a
+/// day's `data/tests.toml`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Case {
+    pub part: u32,
+    /// File name relative to the day's `data` directory
+    pub input: String,
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default)]
+    pub params: BTreeMap<String, toml::Value>,
+    pub answer: Answer,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default, rename = "case")]
+    cases: Vec<Case>,
+}
+
+impl Case {
+    pub fn params(&self) -> Result<Params, String> {
+        self.params
+            .iter()
+            .map(|(name, value)| {
+                let param = match value {
+                    toml::Value::Integer(n) => Param::Int(*n),
+                    toml::Value::Float(x) => Param::Float(*x),
+                    _ => return Err(format!("parameter {} must be a number", name)),
+                };
+                Ok((name.clone(), param))
+            })
+            .collect()
+    }
+
+    /// A short description of the case for reports
+    pub fn label(&self) -> String {
+        let mut label = self.input.clone();
+        if let Some(variant) = &self.variant {
+            label += &format!(" [{}]", variant);
+        }
+        for (name, value) in &self.params {
+            label += &format!(" {}={}", name, value);
+        }
+        label
+    }
+}
+
+pub fn path(day: u32) -> PathBuf {
+    crate::day_dir(day).join("data/tests.toml")
+}
+
+pub fn load(day: u32) -> Result<Vec<Case>, String> {
+    read(&path(day))
+}
+
+pub fn read(path: &Path) -> Result<Vec<Case>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    let manifest: Manifest = toml::from_str(&text)
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))?;
+    Ok(manifest.cases)
+}
+
+/// Sets the answer of each plain case, one without params or a variant, for
+/// the same part and input, or adds the case if there isn't one
+///
+/// The rest of the manifest is left as it was written, comments and all.
+pub fn record(path: &Path, answers: &[(u32, String, Answer)]) -> Result<(), String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    let mut doc: DocumentMut = text
+        .parse()
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))?;
+    let cases = doc
+        .entry("case")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| format!("{} has a case that isn't a [[case]] table", path.display()))?;
+    for (part, input, answer) in answers {
+        let existing = cases.iter_mut().find(|case| {
+            case.get("part").and_then(Item::as_integer) == Some(*part as i64)
+                && case.get("input").and_then(Item::as_str) == Some(input.as_str())
+                && !case.contains_key("variant")
+                && !case.contains_key("params")
+        });
+        match existing {
+            Some(case) => case["answer"] = value(*answer),
+            None => {
+                let mut case = Table::new();
+                case["part"] = value(*part as i64);
+                case["input"] = value(input.as_str());
+                case["answer"] = value(*answer);
+                cases.push(case);
+            }
+        }
+    }
+    fs::write(path, doc.to_string())
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// Runs the case's example through its solver
+pub fn check(day: u32, case: &Case) -> Result<Run, String> {
+    let solver = match &case.variant {
+        Some(variant) => solvers::find_variant(day, case.part, variant),
+        None => solvers::find(day, case.part).ok_or(solvers::Error::UnknownSolver {
+            day,
+            part: case.part,
+        }),
+    }
+    .map_err(|err| err.to_string())?;
+    let path = crate::day_dir(day).join("data").join(&case.input);
+    let text = fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    Ok(crate::run(
+        solver,
+        &text,
+        &case.params()?,
+        &SolverContext::new(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_with_params() {
+        let cases = load(11).unwrap();
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[1].label(), "part1_example.txt expansion=10");
+        for case in &cases {
+            assert_eq!(check(11, case).unwrap().result, Ok(case.answer));
+        }
+        let cases = load(24).unwrap();
+        assert_eq!(
+            cases[0].params(),
+            Ok(Params::from([
+                ("min".to_string(), Param::Float(7.0)),
+                ("max".to_string(), Param::Float(27.0)),
+            ]))
+        );
+        assert_eq!(check(24, &cases[0]).unwrap().result, Ok(2));
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use common::context::CancellationToken;
+use common::simulation::Simulation;
+use solvers::{Param, Params, SolverContext};
+
+/// A day's input parsed once for the queries that look inside it
+enum Model {
+    Day19(HashMap<&'static str, day19::Workflow<'static>>),
+    Day20 {
+        /// The machine before the button was first pressed, boxed like the
+        /// current one since they dwarf the day 19 workflows
+        start: Box<day20::Machine<'static>>,
+        machine: Box<day20::Machine<'static>>,
+    },
+}
+
+impl Model {
+    fn parse(day: u32, text: &'static str) -> Result<Option<Self>, String> {
+        Ok(match day {
+            19 => {
+                let (workflows, _) = day19::parse_input(text).map_err(|err| err.to_string())?;
+                Some(Model::Day19(workflows))
+            }
+            20 => {
+                let start = Box::new(day20::Machine::new(text));
+                Some(Model::Day20 {
+                    machine: start.clone(),
+                    start,
+                })
+            }
+            _ => None,
+        })
+    }
+}
+
+/// A command that looks inside one day's puzzle rather than solving it
+struct Query {
+    day: u32,
+    name: &'static str,
+    usage: &'static str,
+    run: fn(&mut Model, &str, &SolverContext) -> Result<String, String>,
+}
+
+static QUERIES: &[Query] = &[
+    Query {
+        day: 19,
+        name: "trace",
+        usage: "trace {x=..,m=..,a=..,s=..}  show the workflows a part goes through",
+        run: |model, args, _| {
+            let Model::Day19(workflows) = model else {
+                unreachable!("day 19 queries get the day 19 model")
+            };
+            let part = day19::parse_part(args).map_err(|err| err.to_string())?;
+            let (path, accepted) = day19::trace(workflows, &part);
+            Ok(format!(
+                "{} -> {}",
+                path.join(" -> "),
+                if accepted { "A" } else { "R" }
+            ))
+        },
+    },
+    Query {
+        day: 20,
+        name: "presses",
+        usage: "presses N  show the machine state after pressing the button N times",
+        run: |model, args, ctx| {
+            let Model::Day20 { start, machine } = model else {
+                unreachable!("day 20 queries get the day 20 model")
+            };
+            let presses: u64 = args
+                .parse()
+                .map_err(|_| format!("{} is not a number of presses", args))?;
+            // Carry on from the last query unless it went past this one
+            if presses < machine.presses() {
+                *machine = start.clone();
+            }
+            machine.run_until(|machine| {
+                ctx.check().is_err() || (machine.presses() == presses && machine.is_idle())
+            });
+            ctx.check().map_err(|err| format!("stopped: {}", err))?;
+            let state = machine.snapshot();
+            let on: Vec<&str> = state
+                .flip_flops
+                .iter()
+                .filter(|(_, on)| **on)
+                .map(|(name, _)| name.as_str())
+                .collect();
+            Ok(format!(
+                "{} low pulses, {} high pulses, flip flops on: {}",
+                state.low_pulses,
+                state.high_pulses,
+                if on.is_empty() {
+                    "none".to_string()
+                } else {
+                    on.join(" ")
+                }
+            ))
+        },
+    },
+];
+
+/// Cancelled by Ctrl-C while a command is running
+static CTRL_C: OnceLock<CancellationToken> = OnceLock::new();
+
+/// Makes Ctrl-C stop the running command instead of the whole REPL
+///
+/// Solvers that don't check for cancellation can't be stopped this way, so
+/// a second Ctrl-C before the command finishes exits.
+pub fn cancel_on_ctrl_c() {
+    #[cfg(unix)]
+    {
+        extern "C" fn handle(_: libc::c_int) {
+            if let Some(token) = CTRL_C.get() {
+                if token.is_cancelled() {
+                    // SAFETY: `_exit` is async signal safe
+                    unsafe { libc::_exit(130) };
+                }
+                token.cancel();
+            }
+        }
+        CTRL_C.get_or_init(CancellationToken::new);
+        // SAFETY: the handler only touches atomics and calls `_exit`
+        unsafe {
+            libc::signal(
+                libc::SIGINT,
+                handle as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            );
+        }
+    }
+}
+
+/// A day's input kept in memory between commands along with the parameters
+/// the parts are run with
+///
+/// Inputs are leaked so the parsed model can borrow from them, which only
+/// costs one copy of the input per `load` or `reload`.
+pub struct Session {
+    day: u32,
+    path: PathBuf,
+    text: &'static str,
+    /// Parsed the first time a query needs it
+    model: Option<Model>,
+    params: Params,
+    timeout: Option<Duration>,
+}
+
+impl Session {
+    pub fn load(day: u32, path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        Ok(Self {
+            day,
+            path: path.to_path_buf(),
+            text: Box::leak(text.into_boxed_str()),
+            model: None,
+            params: Params::new(),
+            timeout: None,
+        })
+    }
+
+    /// Stops parts and queries that run for longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// A context for one command, with the session's timeout and Ctrl-C
+    fn context(&self) -> SolverContext {
+        let mut ctx = SolverContext::new();
+        if let Some(token) = CTRL_C.get() {
+            token.reset();
+            ctx = ctx.with_cancellation(token.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            ctx = ctx.with_timeout(timeout);
+        }
+        ctx
+    }
+
+    fn model(&mut self) -> Result<Option<&mut Model>, String> {
+        if self.model.is_none() {
+            self.model = Model::parse(self.day, self.text)?;
+        }
+        Ok(self.model.as_mut())
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("day{}> ", self.day)
+    }
+
+    fn queries(&self) -> impl Iterator<Item = &'static Query> + '_ {
+        QUERIES.iter().filter(|query| query.day == self.day)
+    }
+
+    fn help(&self) -> String {
+        let mut out = String::from(
+            "part1 [VARIANT], part2 [VARIANT]  run a part with the current parameters\n\
+             variants                         list the variants of each part\n\
+             set NAME VALUE, unset NAME       change the parameters\n\
+             params                           show the parameters\n\
+             timeout SECONDS, timeout off     limit how long commands run\n\
+             load DAY [PATH]                  switch to another day's input\n\
+             reload                           read the input file again\n\
+             quit\n",
+        );
+        for query in self.queries() {
+            writeln!(out, "{}", query.usage).unwrap();
+        }
+        out
+    }
+
+    fn run_part(&self, part: u32, variant: Option<&str>) -> Result<String, String> {
+        let solver = match variant {
+            Some(variant) => solvers::find_variant(self.day, part, variant),
+            None => solvers::find(self.day, part).ok_or(solvers::Error::UnknownSolver {
+                day: self.day,
+                part,
+            }),
+        }
+        .map_err(|err| err.to_string())?;
+        let params = crate::params_for(solver, &self.params);
+        let run = crate::run(solver, self.text, &params, &self.context());
+        let answer = run.result.map_err(|err| err.to_string())?;
+        Ok(format!("{} ({:.3?})", answer, run.elapsed))
+    }
+
+    /// Runs one line of input and returns what to print
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            [] => Ok(String::new()),
+            ["help"] => Ok(self.help()),
+            ["part1"] => self.run_part(1, None),
+            ["part1", variant] => self.run_part(1, Some(variant)),
+            ["part2"] => self.run_part(2, None),
+            ["part2", variant] => self.run_part(2, Some(variant)),
+            ["variants"] => Ok(solvers::parts(self.day)
+                .into_iter()
+                .map(|part| {
+                    let names: Vec<&str> = solvers::variants(self.day, part)
+                        .map(|solver| solver.variant)
+                        .collect();
+                    format!("part {}: {}\n", part, names.join(", "))
+                })
+                .collect()),
+            ["set", name, value] => {
+                if !crate::takes_param(self.day, name) {
+                    return Err(format!("day {} has no parameter {}", self.day, name));
+                }
+                let param: Param = value
+                    .parse()
+                    .map_err(|err: solvers::Error| err.to_string())?;
+                self.params.insert(name.to_string(), param);
+                Ok(String::new())
+            }
+            ["unset", name] => {
+                self.params.remove(name);
+                Ok(String::new())
+            }
+            ["params"] => {
+                let mut out = String::new();
+                for part in solvers::parts(self.day) {
+                    let solver = solvers::find(self.day, part).unwrap();
+                    let params = solver
+                        .resolve(&crate::params_for(solver, &self.params))
+                        .map_err(|err| err.to_string())?;
+                    for spec in solver.params {
+                        writeln!(
+                            out,
+                            "part {}: {} = {} (default {}), {}",
+                            part, spec.name, params[spec.name], spec.default, spec.help
+                        )
+                        .unwrap();
+                    }
+                }
+                Ok(out)
+            }
+            ["load", day] | ["load", day, _] => {
+                let day: u32 = day.parse().map_err(|_| format!("{} is not a day", day))?;
+                let path = words
+                    .get(2)
+                    .map_or_else(|| crate::input_path(day), PathBuf::from);
+                *self = Session::load(day, &path)?.with_timeout(self.timeout);
+                Ok(format!("loaded {}", path.display()))
+            }
+            ["reload"] => {
+                let params = std::mem::take(&mut self.params);
+                *self = Session::load(self.day, &self.path)?.with_timeout(self.timeout);
+                self.params = params;
+                Ok(format!("reloaded {}", self.path.display()))
+            }
+            ["timeout", "off"] => {
+                self.timeout = None;
+                Ok(String::new())
+            }
+            ["timeout", secs] => {
+                let timeout = secs
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| format!("{} is not a number of seconds", secs))?;
+                self.timeout = Some(timeout);
+                Ok(String::new())
+            }
+            [command, ..] => {
+                let query = self
+                    .queries()
+                    .find(|query| query.name == command)
+                    .ok_or_else(|| format!("unknown command {}, try help", command))?;
+                let ctx = self.context();
+                let model = self.model()?.expect("days with queries have a model");
+                (query.run)(model, line.trim()[command.len()..].trim(), &ctx)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(day: u32) -> Session {
+        Session::load(
+            day,
+            Path::new(&format!("../day{}/data/part1_example.txt", day)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parts_and_params() {
+        let mut session = session(21);
+        assert!(session.execute("set steps 6").unwrap().is_empty());
+        assert_eq!(
+            session.execute("params").unwrap(),
+            "part 1: steps = 6 (default 64), steps the elf takes\n\
+             part 2: steps = 6 (default 26501365), steps the elf takes\n"
+        );
+        assert!(session.execute("set expansion 10").is_err());
+        assert!(session.execute("part1").unwrap().starts_with("16 ("));
+        assert!(session.execute("set steps six").is_err());
+        assert!(session.execute("part1 nonsense").is_err());
+        assert!(session.execute("frobnicate").is_err());
+        session
+            .execute("load 18 ../day18/data/part1_example.txt")
+            .unwrap();
+        assert!(session
+            .execute("part1 shoelace")
+            .unwrap()
+            .starts_with("62 ("));
+        assert_eq!(
+            session.execute("variants").unwrap(),
+            "part 1: flood-fill, shoelace\npart 2: shoelace\n"
+        );
+    }
+
+    #[test]
+    fn queries() {
+        let mut session = session(19);
+        assert_eq!(
+            session.execute("trace {x=787,m=2655,a=1222,s=2876}"),
+            Ok("in -> qqz -> qs -> lnx -> A".to_string())
+        );
+        assert!(session.execute("presses 1").is_err());
+        let mut session = Session::load(20, Path::new("../day20/data/part1_example2.txt")).unwrap();
+        let one = Ok("4 low pulses, 4 high pulses, flip flops on: a b".to_string());
+        assert_eq!(session.execute("presses 1"), one);
+        assert!(session
+            .execute("presses 1000")
+            .unwrap()
+            .starts_with("4250 low"));
+        assert_eq!(session.execute("presses 1"), one);
+
+        assert!(session.execute("timeout 0").unwrap().is_empty());
+        assert!(session
+            .execute("presses 5")
+            .unwrap_err()
+            .contains("deadline"));
+        assert!(session.execute("timeout never").is_err());
+        session.execute("timeout off").unwrap();
+        assert!(session.execute("presses 5").is_ok());
+    }
+}
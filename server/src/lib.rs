@@ -0,0 +1,233 @@
+use std::str;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::context::{CancellationToken, Progress, SolverContext};
+use serde_json::{json, Map, Value};
+use solvers::{Error, Param, ParamSpec, Params};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Handles requests until the server is shut down, one thread per request
+pub fn serve(server: Server, timeout: Duration) {
+    for request in server.incoming_requests() {
+        thread::spawn(move || respond(request, timeout));
+    }
+}
+
+fn respond(mut request: Request, timeout: Duration) {
+    let (status, body) = route(&mut request, timeout);
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+    let _ = request.respond(response);
+}
+
+fn error(status: u16, msg: impl ToString) -> (u16, Value) {
+    (status, json!({ "error": msg.to_string() }))
+}
+
+fn route(request: &mut Request, timeout: Duration) -> (u16, Value) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, ["days"]) => (200, list_days()),
+        (Method::Post, ["days", day, "parts", part]) => {
+            let (Ok(day), Ok(part)) = (day.parse::<u32>(), part.parse::<u32>()) else {
+                return error(404, format!("no such resource {}", path));
+            };
+            let (variant, params) = match parse_query(query) {
+                Ok(query) => query,
+                Err(err) => return error(400, err),
+            };
+            let mut text = String::new();
+            if request.as_reader().read_to_string(&mut text).is_err() {
+                return error(400, "puzzle input must be UTF-8 text");
+            }
+            solve(day, part, variant, text, params, timeout)
+        }
+        (_, ["days"]) | (_, ["days", _, "parts", _]) => error(405, "method not allowed"),
+        _ => error(404, format!("no such resource {}", path)),
+    }
+}
+
+fn param_spec(spec: &ParamSpec) -> Value {
+    let default = match spec.default {
+        Param::Int(n) => json!(n),
+        Param::Float(x) => json!(x),
+    };
+    json!({
+        "name": spec.name,
+        "kind": spec.kind.to_string(),
+        "default": default,
+        "help": spec.help,
+    })
+}
+
+fn list_days() -> Value {
+    let days: Vec<Value> = solvers::days()
+        .into_iter()
+        .map(|day| {
+            let variants: Map<String, Value> = solvers::parts(day)
+                .into_iter()
+                .map(|part| {
+                    let names: Vec<&str> = solvers::variants(day, part)
+                        .map(|solver| solver.variant)
+                        .collect();
+                    (part.to_string(), json!(names))
+                })
+                .collect();
+            let params: Map<String, Value> = solvers::parts(day)
+                .into_iter()
+                .map(|part| {
+                    let specs: Vec<Value> = solvers::find(day, part)
+                        .unwrap()
+                        .params
+                        .iter()
+                        .map(param_spec)
+                        .collect();
+                    (part.to_string(), json!(specs))
+                })
+                .collect();
+            json!({
+                "day": day,
+                "parts": solvers::parts(day),
+                "variants": variants,
+                "params": params,
+            })
+        })
+        .collect();
+    json!({ "days": days })
+}
+
+/// Decodes `%XX` escapes and `+` for spaces in a query string key or value
+fn percent_decode(text: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        rest = after;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = rest
+                    .get(..2)
+                    .and_then(|hex| str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("bad percent escape in {}", text))?;
+                bytes.push(hex);
+                rest = &rest[2..];
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| format!("{} is not UTF-8 once decoded", text))
+}
+
+/// The solver variant, if one is picked with `variant=`, and the parameters
+fn parse_query(query: &str) -> Result<(Option<String>, Params), String> {
+    let mut variant = None;
+    let mut params = Params::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("parameter {} has no value", pair))?;
+        let (name, value) = (percent_decode(name)?, percent_decode(value)?);
+        if name == "variant" {
+            variant = Some(value);
+            continue;
+        }
+        let param = value
+            .parse::<Param>()
+            .map_err(|_| format!("parameter {} must be a number", name))?;
+        params.insert(name, param);
+    }
+    Ok((variant, params))
+}
+
+/// Counts the work a solver reports so it can be included in timeout errors
+#[derive(Default)]
+struct Tally {
+    total: AtomicU64,
+    done: AtomicU64,
+}
+
+impl Progress for Tally {
+    fn start(&self, total: Option<u64>) {
+        self.total.store(total.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    fn advance(&self, delta: u64) {
+        self.done.fetch_add(delta, Ordering::Relaxed);
+    }
+}
+
+impl Tally {
+    fn to_json(&self) -> Value {
+        let total = self.total.load(Ordering::Relaxed);
+        json!({
+            "done": self.done.load(Ordering::Relaxed),
+            "total": if total == 0 { Value::Null } else { total.into() },
+        })
+    }
+}
+
+fn solve(
+    day: u32,
+    part: u32,
+    variant: Option<String>,
+    text: String,
+    params: Params,
+    timeout: Duration,
+) -> (u16, Value) {
+    let solver = match variant {
+        Some(variant) => solvers::find_variant(day, part, &variant),
+        None => solvers::find(day, part).ok_or(Error::UnknownSolver { day, part }),
+    };
+    let solver = match solver {
+        Ok(solver) => solver,
+        Err(err) => return error(404, err),
+    };
+    let cancel = CancellationToken::new();
+    let tally = Arc::new(Tally::default());
+    let ctx = SolverContext::new()
+        .with_cancellation(cancel.clone())
+        .with_timeout(timeout)
+        .with_progress(tally.clone());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let start = Instant::now();
+        let result = solver.run(&text, &params, &ctx);
+        let _ = tx.send((result, start.elapsed()));
+    });
+    // Solvers that check the context stop on their own at the deadline, the
+    // rest are left to finish in the background once the client has its answer
+    let timed_out = || {
+        cancel.cancel();
+        (
+            504,
+            json!({
+                "error": format!("day {} part {} timed out after {:?}", day, part, timeout),
+                "progress": tally.to_json(),
+            }),
+        )
+    };
+    match rx.recv_timeout(timeout + Duration::from_millis(100)) {
+        Ok((Ok(answer), elapsed)) => (
+            200,
+            json!({
+                "day": day,
+                "part": part,
+                "answer": answer,
+                "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+            }),
+        ),
+        Ok((Err(err @ (Error::UnknownSolver { .. } | Error::UnknownVariant { .. })), _)) => {
+            error(404, err)
+        }
+        Ok((Err(err @ (Error::BadParam(_) | Error::BadInput(_))), _)) => error(400, err),
+        Ok((Err(err @ (Error::Checkpoint(_) | Error::Panicked(_))), _)) => error(500, err),
+        Ok((Err(Error::Interrupted(_)), _)) | Err(_) => timed_out(),
+    }
+}
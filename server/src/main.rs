@@ -0,0 +1,35 @@
+use std::env;
+use std::process;
+use std::time::Duration;
+
+use tiny_http::Server;
+
+fn usage() -> ! {
+    eprintln!("usage: server [--addr HOST:PORT] [--timeout SECONDS]");
+    process::exit(2);
+}
+
+fn main() {
+    let mut addr = "127.0.0.1:8080".to_string();
+    let mut timeout = Duration::from_secs(30);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().unwrap_or_else(|| usage()),
+            "--timeout" => {
+                timeout = args
+                    .next()
+                    .and_then(|secs| secs.parse().ok())
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .unwrap_or_else(|| usage());
+            }
+            _ => usage(),
+        }
+    }
+    let server = Server::http(&addr).unwrap_or_else(|err| {
+        eprintln!("Failed to listen on {}: {}", addr, err);
+        process::exit(1);
+    });
+    eprintln!("Listening on http://{}", addr);
+    server::serve(server, timeout);
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+use tiny_http::Server;
+
+fn start(timeout: Duration) -> SocketAddr {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let addr = server.server_addr().to_ip().unwrap();
+    thread::spawn(move || server::serve(server, timeout));
+    addr
+}
+
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        addr,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn list_days() {
+    let addr = start(Duration::from_secs(10));
+    let (status, body) = request(addr, "GET", "/days", "");
+    assert_eq!(status, 200);
+    assert_eq!(body["days"].as_array().unwrap().len(), 25);
+    assert_eq!(body["days"][0]["day"], 1);
+    assert_eq!(body["days"][0]["parts"], serde_json::json!([1, 2]));
+    assert_eq!(
+        body["days"][17]["variants"]["1"],
+        serde_json::json!(["flood-fill", "shoelace"])
+    );
+    assert_eq!(
+        body["days"][20]["params"]["2"][0],
+        serde_json::json!({
+            "name": "steps",
+            "kind": "int",
+            "default": 26501365,
+            "help": "steps the elf takes",
+        })
+    );
+}
+
+#[test]
+fn solve_example() {
+    let addr = start(Duration::from_secs(10));
+    let text = fs::read_to_string("../day1/data/part2_example.txt").unwrap();
+    let (status, body) = request(addr, "POST", "/days/1/parts/2", &text);
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], 281);
+    assert!(body["elapsed_ms"].as_f64().unwrap() >= 0.0);
+}
+
+#[test]
+fn solve_with_params() {
+    let addr = start(Duration::from_secs(10));
+    let text = fs::read_to_string("../day21/data/part1_example.txt").unwrap();
+    let (status, body) = request(addr, "POST", "/days/21/parts/1?steps=6", &text);
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], 16);
+    let (status, _) = request(addr, "POST", "/days/21/parts/1?steps=six", &text);
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn solve_with_variant() {
+    let addr = start(Duration::from_secs(10));
+    let text = fs::read_to_string("../day18/data/part1_example.txt").unwrap();
+    let (status, body) = request(addr, "POST", "/days/18/parts/1?variant=shoe%6Cace", &text);
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], 62);
+    let (status, body) = request(addr, "POST", "/days/18/parts/1?variant=flood+fill", &text);
+    assert_eq!(status, 404);
+    assert!(body["error"].as_str().unwrap().contains("flood fill"));
+    let text = fs::read_to_string("../day21/data/part1_example.txt").unwrap();
+    let (status, body) = request(addr, "POST", "/days/21/parts/1?st%65ps=6", &text);
+    assert_eq!((status, body["answer"].as_i64()), (200, Some(16)));
+    assert_eq!(
+        request(addr, "POST", "/days/21/parts/1?steps=%6", &text).0,
+        400
+    );
+}
+
+#[test]
+fn errors() {
+    let addr = start(Duration::from_secs(10));
+    assert_eq!(request(addr, "POST", "/days/26/parts/1", "").0, 404);
+    assert_eq!(request(addr, "GET", "/days/1/parts/1", "").0, 405);
+    assert_eq!(request(addr, "GET", "/nowhere", "").0, 404);
+    assert_eq!(request(addr, "POST", "/days/1/parts/1", "1\nabc\n").0, 400);
+    assert_eq!(request(addr, "POST", "/days/24/parts/2", "").0, 500);
+}
+
+#[test]
+fn timeout() {
+    let addr = start(Duration::from_millis(50));
+    let text = fs::read_to_string("../day23/data/input.txt").unwrap();
+    let (status, body) = request(addr, "POST", "/days/23/parts/2", &text);
+    assert_eq!(status, 504);
+    assert!(body["error"].as_str().unwrap().contains("timed out"));
+    assert!(body["progress"]["done"].as_u64().is_some());
+}
@@ -0,0 +1,854 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+
+pub use common::checkpoint::SearchError;
+pub use common::context::{Interrupted, SolverContext};
+
+pub type Answer = i64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Param {
+    Int(i64),
+    Float(f64),
+}
+
+impl FromStr for Param {
+    type Err = Error;
+
+    /// Integers become `Int`, anything else that parses as a number `Float`
+    fn from_str(value: &str) -> Result<Self, Error> {
+        if let Ok(n) = value.parse() {
+            Ok(Param::Int(n))
+        } else if let Ok(x) = value.parse() {
+            Ok(Param::Float(x))
+        } else {
+            Err(Error::BadParam(format!("{} is not a number", value)))
+        }
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Param::Int(n) => write!(f, "{}", n),
+            Param::Float(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+pub type Params = HashMap<String, Param>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    UnknownSolver {
+        day: u32,
+        part: u32,
+    },
+    UnknownVariant {
+        day: u32,
+        part: u32,
+        variant: String,
+    },
+    BadParam(String),
+    /// The input couldn't be solved, or its answer doesn't fit in an `Answer`
+    BadInput(String),
+    Interrupted(Interrupted),
+    /// A checkpointed search couldn't load, save or remove its checkpoint
+    Checkpoint(String),
+    Panicked(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownSolver { day, part } => {
+                write!(f, "no solver registered for day {} part {}", day, part)
+            }
+            Error::UnknownVariant { day, part, variant } => {
+                write!(f, "day {} part {} has no variant {}", day, part, variant)
+            }
+            Error::BadParam(msg) => write!(f, "bad parameter: {}", msg),
+            Error::BadInput(msg) => write!(f, "bad input: {}", msg),
+            Error::Interrupted(interrupted) => write!(f, "solver interrupted: {}", interrupted),
+            Error::Checkpoint(msg) => write!(f, "checkpoint failed: {}", msg),
+            Error::Panicked(msg) => write!(f, "solver panicked: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Interrupted> for Error {
+    fn from(interrupted: Interrupted) -> Self {
+        Error::Interrupted(interrupted)
+    }
+}
+
+impl From<SearchError> for Error {
+    fn from(err: SearchError) -> Self {
+        match err {
+            SearchError::Interrupted(interrupted) => Error::Interrupted(interrupted),
+            SearchError::Checkpoint(err) => Error::Checkpoint(err.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    Int,
+    Float,
+}
+
+impl fmt::Display for ParamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamKind::Int => write!(f, "int"),
+            ParamKind::Float => write!(f, "float"),
+        }
+    }
+}
+
+/// A parameter a part can be tuned with, defaulting to the value the puzzle
+/// itself uses
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub default: Param,
+    pub help: &'static str,
+}
+
+impl ParamSpec {
+    const fn int(name: &'static str, default: i64, help: &'static str) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Int,
+            default: Param::Int(default),
+            help,
+        }
+    }
+
+    const fn float(name: &'static str, default: f64, help: &'static str) -> Self {
+        Self {
+            name,
+            kind: ParamKind::Float,
+            default: Param::Float(default),
+            help,
+        }
+    }
+}
+
+/// One way of solving a part, a part can have several variants that should
+/// all give the same answer
+pub struct Solver {
+    pub day: u32,
+    pub part: u32,
+    /// The first variant registered for a part is the one used by default
+    pub variant: &'static str,
+    pub params: &'static [ParamSpec],
+    run: fn(&str, &Params, &SolverContext) -> Result<Answer, Error>,
+}
+
+impl Solver {
+    /// Checks `params` against the solver's schema and fills in the defaults
+    /// for any that weren't given
+    pub fn resolve(&self, params: &Params) -> Result<Params, Error> {
+        if let Some(name) = params
+            .keys()
+            .find(|name| !self.params.iter().any(|spec| spec.name == name.as_str()))
+        {
+            return Err(Error::BadParam(format!(
+                "day {} part {} has no parameter {}",
+                self.day, self.part, name
+            )));
+        }
+        self.params
+            .iter()
+            .map(|spec| {
+                let value = match (spec.kind, params.get(spec.name)) {
+                    (_, None) => spec.default,
+                    (ParamKind::Int, Some(Param::Float(_))) => {
+                        return Err(Error::BadParam(format!("{} must be an integer", spec.name)))
+                    }
+                    (ParamKind::Int, Some(value)) => *value,
+                    (ParamKind::Float, Some(Param::Int(n))) => Param::Float(*n as f64),
+                    (ParamKind::Float, Some(value)) => *value,
+                };
+                Ok((spec.name.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Runs the solver, turning any panic inside the puzzle code into an error
+    pub fn run(&self, text: &str, params: &Params, ctx: &SolverContext) -> Result<Answer, Error> {
+        let params = self.resolve(params)?;
+        panic::catch_unwind(AssertUnwindSafe(|| (self.run)(text, &params, ctx)))
+            .unwrap_or_else(|payload| Err(Error::Panicked(panic_message(payload))))
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// An integer parameter from resolved params, converted to the type the puzzle
+/// code takes
+fn int_param<T: TryFrom<i64>>(params: &Params, name: &str) -> Result<T, Error> {
+    match params[name] {
+        Param::Int(n) => T::try_from(n)
+            .map_err(|_| Error::BadParam(format!("{} is out of range for {}", n, name))),
+        Param::Float(_) => Err(Error::BadParam(format!("{} must be an integer", name))),
+    }
+}
+
+/// A puzzle's answer converted to `Answer`, or `BadInput` if it's too wide
+fn answer<T: TryInto<Answer> + fmt::Display + Copy>(value: T) -> Result<Answer, Error> {
+    value
+        .try_into()
+        .map_err(|_| Error::BadInput(format!("{} does not fit in an answer", value)))
+}
+
+/// Like `answer`, for puzzle code that reports why it couldn't find one
+fn checked_answer<T, E>(result: Result<T, E>) -> Result<Answer, Error>
+where
+    T: TryInto<Answer> + fmt::Display + Copy,
+    E: fmt::Display,
+{
+    answer(result.map_err(|err| Error::BadInput(err.to_string()))?)
+}
+
+fn float_param(params: &Params, name: &str) -> f64 {
+    match params[name] {
+        Param::Int(n) => n as f64,
+        Param::Float(x) => x,
+    }
+}
+
+static SOLVERS: &[Solver] = &[
+    Solver {
+        day: 1,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day1::part1(text.to_string())),
+    },
+    Solver {
+        day: 1,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day1::part2(text.to_string())),
+    },
+    Solver {
+        day: 2,
+        part: 1,
+        variant: "default",
+        params: &[
+            ParamSpec::int("red", day2::PART1_BAG[0].1 as i64, "red cubes in the bag"),
+            ParamSpec::int("green", day2::PART1_BAG[1].1 as i64, "green cubes in the bag"),
+            ParamSpec::int("blue", day2::PART1_BAG[2].1 as i64, "blue cubes in the bag"),
+        ],
+        run: |text, params, _| {
+            let config = ["red", "green", "blue"]
+                .into_iter()
+                .map(|color| Ok((color, int_param(params, color)?)))
+                .collect::<Result<day2::Bag, Error>>()?;
+            answer(day2::possible_games(text.to_string(), &config))
+        },
+    },
+    Solver {
+        day: 2,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day2::part2(text.to_string())),
+    },
+    Solver {
+        day: 3,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day3::part1(text.to_string())),
+    },
+    Solver {
+        day: 3,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day3::part2(text.to_string())),
+    },
+    Solver {
+        day: 4,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day4::part1(text.to_string())),
+    },
+    Solver {
+        day: 4,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day4::part2(text.to_string())),
+    },
+    Solver {
+        day: 5,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day5::part1(text.to_string())),
+    },
+    Solver {
+        day: 5,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day5::part2(text.to_string())),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day6::part1(text.to_string())),
+    },
+    Solver {
+        day: 6,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day6::part2(text.to_string())),
+    },
+    Solver {
+        day: 7,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day7::part1(text.to_string())),
+    },
+    Solver {
+        day: 7,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| checked_answer(day7::part2(text.to_string())),
+    },
+    Solver {
+        day: 8,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day8::part1(text.to_string())),
+    },
+    Solver {
+        day: 8,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day8::part2(text.to_string())),
+    },
+    Solver {
+        day: 9,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day9::part1(text.to_string())),
+    },
+    Solver {
+        day: 9,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day9::part2(text.to_string())),
+    },
+    Solver {
+        day: 10,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day10::part1(text.to_string())),
+    },
+    Solver {
+        day: 10,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day10::part2(text.to_string())),
+    },
+    Solver {
+        day: 11,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day11::part1(text.to_string())),
+    },
+    Solver {
+        day: 11,
+        part: 2,
+        variant: "default",
+        params: &[ParamSpec::int(
+            "expansion",
+            day11::PART2_EXPANSION as i64,
+            "how many times bigger empty rows and columns get",
+        )],
+        run: |text, params, _| {
+            let expansion = int_param(params, "expansion")?;
+            answer(day11::sum_of_distances(text.to_string(), expansion))
+        },
+    },
+    Solver {
+        day: 12,
+        part: 1,
+        variant: "enumerate",
+        params: &[],
+        run: |text, _, _| checked_answer(day12::part1(text.to_string())),
+    },
+    Solver {
+        day: 12,
+        part: 1,
+        variant: "dynamic",
+        params: &[],
+        run: |text, _, _| checked_answer(day12::part1_dynamic(text.to_string())),
+    },
+    Solver {
+        day: 12,
+        part: 2,
+        variant: "dynamic",
+        params: &[],
+        run: |text, _, _| checked_answer(day12::part2(text.to_string())),
+    },
+    Solver {
+        day: 13,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day13::part1(text.to_string())),
+    },
+    Solver {
+        day: 13,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day13::part2(text.to_string())),
+    },
+    Solver {
+        day: 14,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day14::part1(text.to_string())),
+    },
+    Solver {
+        day: 14,
+        part: 2,
+        variant: "default",
+        params: &[ParamSpec::int(
+            "cycles",
+            day14::PART2_CYCLES as i64,
+            "spin cycles to run",
+        )],
+        run: |text, params, _| {
+            let cycles = int_param(params, "cycles")?;
+            answer(day14::load_after_cycles(text.to_string(), cycles))
+        },
+    },
+    Solver {
+        day: 15,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day15::part1(text.to_string())),
+    },
+    Solver {
+        day: 15,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day15::part2(text.to_string())),
+    },
+    Solver {
+        day: 16,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day16::part1(text.to_string())),
+    },
+    Solver {
+        day: 16,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day16::part2(text.to_string())),
+    },
+    Solver {
+        day: 17,
+        part: 1,
+        variant: "default",
+        params: &[ParamSpec::int(
+            "max",
+            day17::PART1_MAX_CONSECUTIVE as i64,
+            "blocks the crucible can go straight for after the first",
+        )],
+        run: |text, params, _| {
+            let max = int_param(params, "max")?;
+            answer(day17::min_heat_loss(text.to_string(), None, max))
+        },
+    },
+    Solver {
+        day: 17,
+        part: 2,
+        variant: "default",
+        params: &[
+            ParamSpec::int(
+                "min",
+                day17::PART2_MIN_BEFORE_TURN as i64,
+                "blocks the crucible has to go straight for before turning",
+            ),
+            ParamSpec::int(
+                "max",
+                day17::PART2_MAX_CONSECUTIVE as i64,
+                "blocks the crucible can go straight for after the first",
+            ),
+        ],
+        run: |text, params, _| {
+            let min = int_param(params, "min")?;
+            let max = int_param(params, "max")?;
+            answer(day17::min_heat_loss(text.to_string(), Some(min), max))
+        },
+    },
+    Solver {
+        day: 18,
+        part: 1,
+        variant: "flood-fill",
+        params: &[],
+        run: |text, _, _| answer(day18::part1(text.to_string())),
+    },
+    Solver {
+        day: 18,
+        part: 1,
+        variant: "shoelace",
+        params: &[],
+        run: |text, _, _| answer(day18::part1_shoelace(text.to_string())),
+    },
+    Solver {
+        day: 18,
+        part: 2,
+        variant: "shoelace",
+        params: &[],
+        run: |text, _, _| answer(day18::part2(text.to_string())),
+    },
+    Solver {
+        day: 19,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day19::part1(text.to_string())),
+    },
+    Solver {
+        day: 19,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day19::part2(text.to_string())),
+    },
+    Solver {
+        day: 20,
+        part: 1,
+        variant: "default",
+        params: &[ParamSpec::int(
+            "presses",
+            day20::PART1_PRESSES as i64,
+            "times the button is pressed",
+        )],
+        run: |text, params, _| {
+            let presses = int_param(params, "presses")?;
+            answer(day20::pulse_product(text.to_string(), presses))
+        },
+    },
+    Solver {
+        day: 20,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day20::part2(text.to_string())),
+    },
+    Solver {
+        day: 21,
+        part: 1,
+        variant: "default",
+        params: &[ParamSpec::int(
+            "steps",
+            day21::PART1_STEPS as i64,
+            "steps the elf takes",
+        )],
+        run: |text, params, _| {
+            let steps = int_param(params, "steps")?;
+            answer(day21::part1(text.to_string(), steps))
+        },
+    },
+    Solver {
+        day: 21,
+        part: 2,
+        variant: "default",
+        params: &[ParamSpec::int(
+            "steps",
+            day21::PART2_STEPS as i64,
+            "steps the elf takes",
+        )],
+        run: |text, params, _| {
+            let steps = int_param(params, "steps")?;
+            answer(day21::part2(text.to_string(), steps))
+        },
+    },
+    Solver {
+        day: 22,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day22::part1(text.to_string())),
+    },
+    Solver {
+        day: 22,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day22::part2(text.to_string())),
+    },
+    Solver {
+        day: 23,
+        part: 1,
+        variant: "default",
+        params: &[],
+        run: |text, _, ctx| answer(day23::part1_with_context(text.to_string(), ctx)?),
+    },
+    Solver {
+        day: 23,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, ctx| answer(day23::part2_with_context(text.to_string(), ctx)?),
+    },
+    Solver {
+        day: 24,
+        part: 1,
+        variant: "default",
+        params: &[
+            ParamSpec::float("min", day24::PART1_MIN, "lower bound of the test area"),
+            ParamSpec::float("max", day24::PART1_MAX, "upper bound of the test area"),
+        ],
+        run: |text, params, _| {
+            let min = float_param(params, "min");
+            let max = float_param(params, "max");
+            answer(day24::part1(text.to_string(), min, max))
+        },
+    },
+    Solver {
+        day: 24,
+        part: 2,
+        variant: "default",
+        params: &[],
+        run: |text, _, _| answer(day24::part2(text.to_string())),
+    },
+    Solver {
+        day: 25,
+        part: 1,
+        variant: "brute-force",
+        params: &[],
+        run: |text, _, ctx| answer(day25::part1_with_context(text.to_string(), ctx)?),
+    },
+    Solver {
+        day: 25,
+        part: 1,
+        variant: "stoer-wagner",
+        params: &[],
+        run: |text, _, _| answer(day25::part2(text.to_string())),
+    },
+    Solver {
+        day: 25,
+        part: 2,
+        variant: "stoer-wagner",
+        params: &[],
+        run: |text, _, _| answer(day25::part2(text.to_string())),
+    },
+];
+
+pub fn solvers() -> &'static [Solver] {
+    SOLVERS
+}
+
+/// The default variant for the part
+pub fn find(day: u32, part: u32) -> Option<&'static Solver> {
+    variants(day, part).next()
+}
+
+pub fn variants(day: u32, part: u32) -> impl Iterator<Item = &'static Solver> {
+    SOLVERS
+        .iter()
+        .filter(move |solver| solver.day == day && solver.part == part)
+}
+
+pub fn find_variant(day: u32, part: u32, variant: &str) -> Result<&'static Solver, Error> {
+    let mut variants = variants(day, part).peekable();
+    if variants.peek().is_none() {
+        return Err(Error::UnknownSolver { day, part });
+    }
+    variants
+        .find(|solver| solver.variant == variant)
+        .ok_or_else(|| Error::UnknownVariant {
+            day,
+            part,
+            variant: variant.to_string(),
+        })
+}
+
+/// The parts registered for `day`, each listed once however many variants it has
+pub fn parts(day: u32) -> Vec<u32> {
+    let mut parts: Vec<u32> = SOLVERS
+        .iter()
+        .filter(|solver| solver.day == day)
+        .map(|solver| solver.part)
+        .collect();
+    parts.dedup();
+    parts
+}
+
+pub fn days() -> Vec<u32> {
+    let mut days: Vec<u32> = SOLVERS.iter().map(|solver| solver.day).collect();
+    days.dedup();
+    days
+}
+
+pub fn solve(day: u32, part: u32, text: &str, params: &Params) -> Result<Answer, Error> {
+    solve_with_context(day, part, text, params, &SolverContext::new())
+}
+
+pub fn solve_with_context(
+    day: u32,
+    part: u32,
+    text: &str,
+    params: &Params,
+    ctx: &SolverContext,
+) -> Result<Answer, Error> {
+    find(day, part)
+        .ok_or(Error::UnknownSolver { day, part })?
+        .run(text, params, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn example() {
+        let text = fs::read_to_string("../day1/data/part1_example.txt").unwrap();
+        assert_eq!(solve(1, 1, &text, &Params::new()), Ok(142))
+    }
+
+    #[test]
+    fn example_with_params() {
+        let text = fs::read_to_string("../day21/data/part1_example.txt").unwrap();
+        let params = Params::from([("steps".to_string(), Param::Int(6))]);
+        assert_eq!(solve(21, 1, &text, &params), Ok(16))
+    }
+
+    #[test]
+    fn param_schema() {
+        let solver = find(24, 1).unwrap();
+        let params = solver.resolve(&Params::new()).unwrap();
+        assert_eq!(params["max"], Param::Float(400000000000000.0));
+        let params = Params::from([("min".to_string(), Param::Int(7))]);
+        assert_eq!(solver.resolve(&params).unwrap()["min"], Param::Float(7.0));
+        let params = Params::from([("steps".to_string(), Param::Float(6.5))]);
+        assert!(matches!(solve(21, 1, "", &params), Err(Error::BadParam(_))));
+        let params = Params::from([("steps".to_string(), Param::Int(-1))]);
+        assert!(matches!(solve(21, 1, "", &params), Err(Error::BadParam(_))));
+        let text = fs::read_to_string("../day11/data/part1_example.txt").unwrap();
+        let params = Params::from([("expansion".to_string(), Param::Int(10))]);
+        assert_eq!(solve(11, 2, &text, &params), Ok(1030));
+    }
+
+    #[test]
+    fn unknown_param() {
+        let params = Params::from([("steps".to_string(), Param::Int(6))]);
+        assert!(matches!(solve(1, 1, "", &params), Err(Error::BadParam(_))))
+    }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(
+            solve(1, 1, "1\nabc\n", &Params::new()),
+            Err(Error::BadInput("line 2: no digits in the line".to_string()))
+        );
+        for day in [4, 7, 12] {
+            let Err(Error::BadInput(msg)) = solve(day, 1, "oops\n", &Params::new()) else {
+                panic!("day {} accepted bad input", day)
+            };
+            assert!(msg.starts_with("line 1, "), "{}", msg);
+        }
+        assert!(matches!(
+            solve(3, 1, "99999999999*\n", &Params::new()),
+            Err(Error::BadInput(_))
+        ));
+        assert!(matches!(
+            checked_answer(Ok::<u128, Error>(1 << 64)),
+            Err(Error::BadInput(_))
+        ));
+        assert!(matches!(answer(u64::MAX), Err(Error::BadInput(_))));
+        assert_eq!(answer(42usize), Ok(42));
+    }
+
+    #[test]
+    fn unknown_solver() {
+        assert_eq!(
+            solve(26, 1, "", &Params::new()),
+            Err(Error::UnknownSolver { day: 26, part: 1 })
+        )
+    }
+
+    #[test]
+    fn variants_agree() {
+        for (day, part) in [(12, 1), (18, 1)] {
+            let text = fs::read_to_string(format!("../day{}/data/part1_example.txt", day)).unwrap();
+            let answers: Vec<_> = variants(day, part)
+                .map(|solver| solver.run(&text, &Params::new(), &SolverContext::new()))
+                .collect();
+            assert_eq!(answers.len(), 2);
+            assert_eq!(answers[0], answers[1]);
+        }
+        assert_eq!(find(18, 1).unwrap().variant, "flood-fill");
+        assert_eq!(find_variant(18, 1, "shoelace").unwrap().variant, "shoelace");
+        assert!(matches!(
+            find_variant(18, 1, "quadtree"),
+            Err(Error::UnknownVariant { .. })
+        ));
+        assert_eq!(parts(18), vec![1, 2]);
+    }
+
+    #[test]
+    fn interrupted() {
+        let text = fs::read_to_string("../day23/data/part2_example.txt").unwrap();
+        let ctx = SolverContext::new().with_timeout(std::time::Duration::ZERO);
+        assert_eq!(
+            solve_with_context(23, 2, &text, &Params::new(), &ctx),
+            Err(Error::Interrupted(Interrupted::DeadlineExceeded))
+        )
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        assert!(matches!(
+            solve(24, 2, "", &Params::new()),
+            Err(Error::Panicked(_))
+        ))
+    }
+}